@@ -0,0 +1,3170 @@
+"""Pure-Python fallbacks used by the Rust bridge when the native core is unavailable."""
+
+from __future__ import annotations
+
+import base64
+import binascii
+import codecs
+import difflib
+import errno
+import hashlib
+import heapq
+import json
+import locale
+import os
+import posixpath
+import random
+import re
+import signal
+import stat
+import struct
+import subprocess
+import tempfile
+import threading
+import time
+import unicodedata
+from concurrent.futures import ThreadPoolExecutor
+from typing import Callable, Iterator, NamedTuple, Optional, Tuple, Union
+
+
+_HUNK_RE = re.compile(r"^@@ -(\d+)(?:,(\d+))? \+(\d+)(?:,(\d+))? @@")
+
+
+def _validate_patch_python(patch: str) -> Tuple[bool, str]:
+    if not patch or not patch.strip():
+        return False, "Patch is empty"
+
+    lines = patch.splitlines()
+    saw_hunk = False
+    in_hunk = False
+    expected_old = 0
+    expected_new = 0
+    seen_old = 0
+    seen_new = 0
+
+    for line in lines:
+        m = _HUNK_RE.match(line)
+        if m:
+            if in_hunk:
+                if expected_old > 0 and seen_old != expected_old:
+                    return False, f"Old-side hunk line count mismatch: expected {expected_old}, got {seen_old}"
+                if expected_new > 0 and seen_new != expected_new:
+                    return False, f"New-side hunk line count mismatch: expected {expected_new}, got {seen_new}"
+            saw_hunk = True
+            in_hunk = True
+            expected_old = int(m.group(2) or "1")
+            expected_new = int(m.group(4) or "1")
+            seen_old = 0
+            seen_new = 0
+            continue
+
+        if not in_hunk:
+            continue
+
+        if line.startswith(" "):
+            seen_old += 1
+            seen_new += 1
+        elif line.startswith("-"):
+            seen_old += 1
+        elif line.startswith("+"):
+            seen_new += 1
+        elif line.startswith("\\ No newline"):
+            pass
+        elif line.startswith("--- ") or line.startswith("+++ "):
+            pass
+        else:
+            return False, f"Unsupported patch line in hunk: {line}"
+
+    if not saw_hunk:
+        return False, "Patch must contain at least one unified diff hunk (@@ ...)"
+
+    if expected_old > 0 and seen_old != expected_old:
+        return False, f"Old-side hunk line count mismatch: expected {expected_old}, got {seen_old}"
+    if expected_new > 0 and seen_new != expected_new:
+        return False, f"New-side hunk line count mismatch: expected {expected_new}, got {seen_new}"
+
+    return True, "ok"
+
+
+def _locate_hunk_python(
+    original: str,
+    hunk_context: list[str],
+    stated_line: int,
+    window: int,
+    max_fuzz: int,
+) -> tuple[bool, Optional[int], int, str]:
+    if not hunk_context:
+        return False, None, 0, "Hunk context is empty"
+    if window < 0 or max_fuzz < 0:
+        return False, None, 0, "window and max_fuzz must be non-negative"
+
+    lines = original.splitlines()
+    wanted = [line.rstrip("\r\n") for line in hunk_context]
+    span = len(wanted)
+    if span > len(lines):
+        return True, None, 0, ""
+
+    stated = max(stated_line - 1, 0)
+    first = max(stated - window, 0)
+    last = min(stated + window, len(lines) - span)
+
+    best: Optional[tuple[int, int, int]] = None
+    for start in range(first, last + 1):
+        cost = sum(1 for i, want in enumerate(wanted) if lines[start + i] != want)
+        if cost > max_fuzz or cost >= span:
+            continue
+        key = (cost, abs(start - stated), start)
+        if best is None or key < best:
+            best = key
+
+    if best is None:
+        return True, None, 0, ""
+    return True, best[2], best[0], ""
+
+
+def _get_permissions_python(path: str) -> tuple[bool, int, bool, str]:
+    try:
+        st = os.stat(path)
+    except OSError as e:
+        return False, 0, False, str(e)
+
+    if os.name == "nt":
+        readonly = not (st.st_mode & stat.S_IWRITE)
+        return True, 0o444 if readonly else 0o666, readonly, ""
+
+    mode = stat.S_IMODE(st.st_mode)
+    return True, mode, not (mode & stat.S_IWUSR), ""
+
+
+def _set_permissions_python(path: str, mode: int) -> tuple[bool, str]:
+    if not os.path.exists(path):
+        return False, f"File not found: {path}"
+    try:
+        if os.name == "nt":
+            writable = bool(mode & stat.S_IWUSR)
+            os.chmod(path, stat.S_IWRITE | stat.S_IREAD if writable else stat.S_IREAD)
+        else:
+            os.chmod(path, stat.S_IMODE(mode))
+    except OSError as e:
+        return False, str(e)
+    return True, ""
+
+
+_READ_CHUNK_BYTES = 65536
+_READER_GRACE_SECONDS = 1.0
+
+_TRACKED_HANDLES: set[int] = set()
+_TRACKED_HANDLES_LOCK = threading.Lock()
+
+
+def _track_handle(handle: object) -> None:
+    with _TRACKED_HANDLES_LOCK:
+        _TRACKED_HANDLES.add(id(handle))
+
+
+def _untrack_handle(handle: object) -> None:
+    with _TRACKED_HANDLES_LOCK:
+        _TRACKED_HANDLES.discard(id(handle))
+
+
+def _debug_open_handles_python() -> int:
+    with _TRACKED_HANDLES_LOCK:
+        return len(_TRACKED_HANDLES)
+
+
+def _stream_argv_python(
+    argv: list[str],
+    cwd: str,
+    timeout_seconds: float,
+    on_chunk: Callable[[str, bytes], Optional[bool]],
+    env: Optional[dict[str, str]] = None,
+) -> tuple[Optional[int], bool, str]:
+    """
+    Run argv, feeding ("stdout"|"stderr", bytes) chunks to on_chunk one at a time.
+
+    on_chunk may return True to kill the process; remaining output is then drained unseen.
+    On POSIX the child leads its own session so a timeout or abort also kills any
+    grandchildren still holding the pipes.
+    """
+    if not argv:
+        return None, False, "Empty command"
+    try:
+        process = subprocess.Popen(
+            argv,
+            cwd=cwd or None,
+            stdin=subprocess.DEVNULL,
+            stdout=subprocess.PIPE,
+            stderr=subprocess.PIPE,
+            env=env,
+            start_new_session=os.name != "nt",
+        )
+    except (OSError, ValueError) as e:
+        return None, False, str(e)
+    _track_handle(process)
+    try:
+        return _drive_process_python(process, timeout_seconds, on_chunk)
+    finally:
+        _untrack_handle(process)
+
+
+def _drive_process_python(
+    process: subprocess.Popen,
+    timeout_seconds: float,
+    on_chunk: Callable[[str, bytes], Optional[bool]],
+) -> tuple[Optional[int], bool, str]:
+    lock = threading.Lock()
+    stopped = threading.Event()
+
+    def pump(pipe, stream: str) -> None:
+        with pipe:
+            for chunk in iter(lambda: pipe.read1(_READ_CHUNK_BYTES), b""):
+                with lock:
+                    if stopped.is_set():
+                        continue
+                    if on_chunk(stream, chunk):
+                        stopped.set()
+                        _kill_process_group(process)
+
+    readers = [
+        threading.Thread(target=pump, args=(process.stdout, "stdout"), daemon=True),
+        threading.Thread(target=pump, args=(process.stderr, "stderr"), daemon=True),
+    ]
+    for reader in readers:
+        reader.start()
+
+    deadline = time.monotonic() + timeout_seconds if timeout_seconds > 0 else None
+    timed_out = False
+    try:
+        process.wait(timeout=timeout_seconds if timeout_seconds > 0 else None)
+    except subprocess.TimeoutExpired:
+        timed_out = True
+        _kill_process_group(process)
+        process.wait()
+    # A grandchild that outlives the child keeps the pipes open; it gets the rest of
+    # the timeout to finish before the whole group is killed.
+    for reader in readers:
+        reader.join(None if deadline is None else max(0.0, deadline - time.monotonic()))
+    if any(reader.is_alive() for reader in readers):
+        timed_out = timed_out or not stopped.is_set()
+        _kill_process_group(process)
+        for reader in readers:
+            reader.join(_READER_GRACE_SECONDS)
+    return process.returncode, timed_out, ""
+
+
+def _kill_process_group(process: subprocess.Popen) -> None:
+    if os.name != "nt":
+        try:
+            os.killpg(process.pid, signal.SIGKILL)
+            return
+        except OSError:
+            pass
+    try:
+        process.kill()
+    except OSError:
+        pass
+
+
+def _command_outcome(
+    returncode: Optional[int],
+    timed_out: bool,
+    error: str,
+    timeout_seconds: float,
+) -> tuple[bool, int, str]:
+    if error:
+        return False, -1, error
+    if timed_out:
+        return False, -1, f"Command timed out after {timeout_seconds}s"
+    code = int(returncode if returncode is not None else -1)
+    if code != 0:
+        return False, code, f"Exit code: {code}"
+    return True, 0, ""
+
+
+def _execute_command_argv_bounded_python(
+    argv: list[str],
+    cwd: str,
+    timeout_seconds: float,
+    max_output_bytes: int,
+) -> tuple[bool, int, str, str, str, int, int]:
+    limit = max(int(max_output_bytes), 0)
+    tails = {"stdout": bytearray(), "stderr": bytearray()}
+    totals = {"stdout": 0, "stderr": 0}
+
+    def on_chunk(stream: str, chunk: bytes) -> None:
+        totals[stream] += len(chunk)
+        tail = tails[stream]
+        tail.extend(chunk)
+        if len(tail) > limit:
+            del tail[: len(tail) - limit]
+
+    returncode, timed_out, error = _stream_argv_python(argv, cwd, timeout_seconds, on_chunk)
+    success, code, error = _command_outcome(returncode, timed_out, error, timeout_seconds)
+    return (
+        success,
+        code,
+        tails["stdout"].decode("utf-8", errors="replace"),
+        tails["stderr"].decode("utf-8", errors="replace"),
+        error,
+        totals["stdout"],
+        totals["stderr"],
+    )
+
+
+def _read_line_ranges_python(
+    path: str,
+    ranges: list[tuple[int, int]],
+) -> tuple[bool, list[list[str]], str]:
+    for start, end in ranges:
+        if start < 1 or end < start:
+            return False, [], f"Invalid line range: {start}-{end}"
+    try:
+        with open(path, "rb") as handle:
+            lines = handle.read().decode("utf-8", errors="replace").splitlines()
+    except OSError as e:
+        return False, [], str(e)
+    return True, [lines[start - 1 : end] for start, end in ranges], ""
+
+
+class _GitignoreRules:
+    """Minimal .gitignore matcher covering the pattern forms used in practice."""
+
+    def __init__(self) -> None:
+        self._rules: list[tuple[str, re.Pattern[str], bool, bool]] = []
+
+    def load(self, base: str, gitignore_path: str) -> None:
+        try:
+            with open(gitignore_path, "r", encoding="utf-8", errors="replace") as handle:
+                raw_lines = handle.read().splitlines()
+        except OSError:
+            return
+        for raw in raw_lines:
+            line = raw.rstrip()
+            if not line or line.startswith("#"):
+                continue
+            negate = line.startswith("!")
+            if negate:
+                line = line[1:]
+            if line.startswith("\\"):
+                line = line[1:]
+            dir_only = line.endswith("/")
+            line = line.rstrip("/")
+            if not line:
+                continue
+            anchored = "/" in line
+            body = _glob_to_regex(line.lstrip("/"))
+            pattern = re.compile(body if anchored else f"(?:.*/)?{body}")
+            self._rules.append((base, pattern, negate, dir_only))
+
+    def is_ignored(self, rel_path: str, is_dir: bool) -> bool:
+        ignored = False
+        for base, pattern, negate, dir_only in self._rules:
+            if dir_only and not is_dir:
+                continue
+            if base:
+                if not rel_path.startswith(base + "/"):
+                    continue
+                candidate = rel_path[len(base) + 1 :]
+            else:
+                candidate = rel_path
+            if pattern.fullmatch(candidate):
+                ignored = not negate
+        return ignored
+
+
+def _glob_to_regex(glob: str) -> str:
+    out: list[str] = []
+    i = 0
+    while i < len(glob):
+        ch = glob[i]
+        if glob.startswith("**/", i):
+            out.append("(?:.*/)?")
+            i += 3
+        elif glob.startswith("/**", i) and i + 3 == len(glob):
+            out.append("/.*")
+            i += 3
+        elif glob.startswith("**", i):
+            out.append(".*")
+            i += 2
+        elif ch == "*":
+            out.append("[^/]*")
+            i += 1
+        elif ch == "?":
+            out.append("[^/]")
+            i += 1
+        elif ch == "[":
+            close = glob.find("]", i + 1)
+            if close == -1:
+                out.append(re.escape(ch))
+                i += 1
+            else:
+                inner = glob[i + 1 : close]
+                if inner.startswith("!"):
+                    inner = "^" + inner[1:]
+                out.append(f"[{inner}]")
+                i = close + 1
+        else:
+            out.append(re.escape(ch))
+            i += 1
+    return "".join(out)
+
+
+def _iter_entries_python(
+    root: str,
+    respect_gitignore: bool = True,
+) -> Iterator[tuple[str, str, bool]]:
+    """Lazily yield (posix relpath, absolute path, is_dir) for entries under root, skipping .git."""
+    rules = _GitignoreRules()
+    for dirpath, dirnames, filenames in os.walk(root):
+        rel_dir = os.path.relpath(dirpath, root).replace(os.sep, "/")
+        rel_dir = "" if rel_dir == "." else rel_dir
+        if respect_gitignore and ".gitignore" in filenames:
+            rules.load(rel_dir, os.path.join(dirpath, ".gitignore"))
+
+        kept_dirs = []
+        for name in sorted(dirnames):
+            rel = f"{rel_dir}/{name}" if rel_dir else name
+            if name == ".git" or (respect_gitignore and rules.is_ignored(rel, True)):
+                continue
+            kept_dirs.append(name)
+            yield rel, os.path.join(dirpath, name), True
+        dirnames[:] = kept_dirs
+
+        for name in filenames:
+            rel = f"{rel_dir}/{name}" if rel_dir else name
+            if respect_gitignore and rules.is_ignored(rel, False):
+                continue
+            yield rel, os.path.join(dirpath, name), False
+
+
+def _walk_files_python(root: str, respect_gitignore: bool = True) -> list[tuple[str, str]]:
+    """Return sorted (posix relpath, absolute path) pairs for files under root, skipping .git."""
+    entries = _iter_entries_python(root, respect_gitignore)
+    return sorted((rel, path) for rel, path, is_dir in entries if not is_dir)
+
+
+def _sha256_file(path: str) -> str:
+    digest = hashlib.sha256()
+    with open(path, "rb") as handle:
+        for chunk in iter(lambda: handle.read(_READ_CHUNK_BYTES), b""):
+            digest.update(chunk)
+    return digest.hexdigest()
+
+
+def _hash_files_python(paths: list[str]) -> list[str]:
+    """Hash files in parallel, preserving input order."""
+    with ThreadPoolExecutor() as pool:
+        return list(pool.map(_sha256_file, paths))
+
+
+def _normalize_extensions(extensions: list[str]) -> set[str]:
+    normalized = set()
+    for ext in extensions:
+        ext = str(ext).strip().lower()
+        if ext:
+            normalized.add(ext if ext.startswith(".") else f".{ext}")
+    return normalized
+
+
+def _hash_tree_python(
+    root: str,
+    respect_gitignore: bool,
+    ignore_extensions: list[str],
+) -> tuple[bool, str, str]:
+    if not os.path.isdir(root):
+        return False, "", f"Not a directory: {root}"
+    ignored = _normalize_extensions(ignore_extensions)
+    try:
+        files = [
+            (rel, path)
+            for rel, path in _walk_files_python(root, respect_gitignore)
+            if os.path.splitext(rel)[1].lower() not in ignored
+        ]
+        digests = _hash_files_python([path for _, path in files])
+    except OSError as e:
+        return False, "", str(e)
+
+    combined = hashlib.sha256()
+    combined.update(("ignore:" + ",".join(sorted(ignored)) + "\n").encode("utf-8"))
+    for (rel, _), file_digest in zip(files, digests):
+        combined.update(f"{rel}\0{file_digest}\n".encode("utf-8"))
+    return True, combined.hexdigest(), ""
+
+
+def _atomic_write_bytes(path: str, data: bytes, durable: bool = False) -> None:
+    """Write via a sibling temp file and rename, keeping the target's permissions."""
+    directory = os.path.dirname(os.path.abspath(path))
+    prefix = f".{os.path.basename(path)}."
+    fd, tmp_path = tempfile.mkstemp(dir=directory, prefix=prefix, suffix=".tmp")
+    try:
+        with os.fdopen(fd, "wb") as handle:
+            handle.write(data)
+            if durable:
+                handle.flush()
+                os.fsync(handle.fileno())
+        try:
+            os.chmod(tmp_path, stat.S_IMODE(os.stat(path).st_mode))
+        except OSError:
+            pass
+        os.replace(tmp_path, path)
+        if durable:
+            _fsync_directory(directory)
+    except BaseException:
+        try:
+            os.unlink(tmp_path)
+        except OSError:
+            pass
+        raise
+
+
+def _transform_file_python(path: str, callback: Callable[[str], str]) -> tuple[bool, int, str]:
+    try:
+        with open(path, "rb") as handle:
+            content = handle.read().decode("utf-8", errors="replace")
+    except OSError as e:
+        return False, 0, str(e)
+
+    try:
+        updated = callback(content)
+    except Exception as e:
+        return False, 0, f"Transform callback failed: {e}"
+    if not isinstance(updated, str):
+        return False, 0, f"Transform callback must return str, got {type(updated).__name__}"
+
+    data = updated.encode("utf-8")
+    try:
+        _atomic_write_bytes(path, data)
+    except OSError as e:
+        return False, 0, str(e)
+    return True, len(data), ""
+
+
+class _Hunk(NamedTuple):
+    old_start: int
+    old_count: int
+    new_start: int
+    new_count: int
+    lines: list[tuple[str, str]]
+
+
+def _parse_hunks_python(patch: str) -> list[_Hunk]:
+    """Parse a patch into hunks of (token, text) body lines; file headers are skipped."""
+    hunks: list[_Hunk] = []
+    for line in patch.splitlines():
+        m = _HUNK_RE.match(line)
+        if m:
+            hunks.append(
+                _Hunk(
+                    int(m.group(1)),
+                    int(m.group(2) or "1"),
+                    int(m.group(3)),
+                    int(m.group(4) or "1"),
+                    [],
+                )
+            )
+            continue
+        if not hunks or line.startswith("--- ") or line.startswith("+++ "):
+            continue
+        if line.startswith("\\"):
+            continue
+        token, text = (line[0], line[1:]) if line else (" ", "")
+        if token in (" ", "-", "+"):
+            hunks[-1].lines.append((token, text))
+    return hunks
+
+
+def _remap_line_python(patch: str, old_line: int) -> tuple[bool, Optional[int], str]:
+    if old_line < 1:
+        return False, None, "old_line must be >= 1"
+    ok, reason = _validate_patch_python(patch)
+    if not ok:
+        return False, None, reason
+
+    shift = 0
+    for hunk in _parse_hunks_python(patch):
+        region_start = hunk.old_start if hunk.old_count else hunk.old_start + 1
+        if old_line < region_start:
+            return True, old_line + shift, ""
+        if old_line < region_start + hunk.old_count:
+            old_no = region_start
+            new_no = hunk.new_start
+            for token, _ in hunk.lines:
+                if token == " ":
+                    if old_no == old_line:
+                        return True, new_no, ""
+                    old_no += 1
+                    new_no += 1
+                elif token == "-":
+                    if old_no == old_line:
+                        return True, None, ""
+                    old_no += 1
+                else:
+                    new_no += 1
+        shift += hunk.new_count - hunk.old_count
+    return True, old_line + shift, ""
+
+
+def _chunk_text_python(
+    content: str,
+    window_lines: int,
+    overlap_lines: int,
+) -> list[tuple[int, int, str]]:
+    lines = content.splitlines(keepends=True)
+    step = window_lines - overlap_lines
+    chunks: list[tuple[int, int, str]] = []
+    start = 0
+    while start < len(lines):
+        end = min(start + window_lines, len(lines))
+        chunks.append((start + 1, end, "".join(lines[start:end])))
+        if end == len(lines):
+            break
+        start += step
+    return chunks
+
+
+def _classify_patch_lines(patch: str) -> list[tuple[str, str]]:
+    """
+    Tag each patch line as old_header, new_header, hunk, context, remove, add, marker or meta.
+
+    Hunk bodies are delimited by the header counts, so body lines that happen to start
+    with "--- " or "+++ " are not mistaken for file headers.
+    """
+    tagged: list[tuple[str, str]] = []
+    old_left = new_left = 0
+    lines = patch.splitlines()
+    for index, line in enumerate(lines):
+        m = _HUNK_RE.match(line)
+        in_body = old_left > 0 or new_left > 0
+        next_line = lines[index + 1] if index + 1 < len(lines) else ""
+        if m:
+            tagged.append(("hunk", line))
+            old_left = int(m.group(2) or "1")
+            new_left = int(m.group(4) or "1")
+        elif line.startswith("\\") and tagged and tagged[-1][0] in ("context", "remove", "add"):
+            tagged.append(("marker", line))
+        elif in_body and line.startswith("-"):
+            tagged.append(("remove", line))
+            old_left -= 1
+        elif in_body and line.startswith("+"):
+            tagged.append(("add", line))
+            new_left -= 1
+        elif in_body:
+            tagged.append(("context", line))
+            old_left -= 1
+            new_left -= 1
+        elif line.startswith("--- ") and next_line.startswith("+++ "):
+            tagged.append(("old_header", line))
+        elif line.startswith("+++ ") and tagged and tagged[-1][0] == "old_header":
+            tagged.append(("new_header", line))
+        else:
+            tagged.append(("meta", line))
+    return tagged
+
+
+def _join_patch_lines(lines: list[str], like: str) -> str:
+    text = "\n".join(lines)
+    if lines and like.endswith("\n"):
+        text += "\n"
+    return text
+
+
+def _reverse_patch_python(patch: str) -> tuple[bool, str, str]:
+    ok, reason = _validate_patch_python(patch)
+    if not ok:
+        return False, "", reason
+
+    out: list[str] = []
+    old_header = ""
+    for kind, line in _classify_patch_lines(patch):
+        m = _HUNK_RE.match(line) if kind == "hunk" else None
+        if m is not None:
+            old_range = m.group(1) + (f",{m.group(2)}" if m.group(2) is not None else "")
+            new_range = m.group(3) + (f",{m.group(4)}" if m.group(4) is not None else "")
+            out.append(f"@@ -{new_range} +{old_range} @@{line[m.end():]}")
+        elif kind == "old_header":
+            old_header = line[4:]
+        elif kind == "new_header":
+            out.append(f"--- {line[4:]}")
+            out.append(f"+++ {old_header}")
+        elif kind == "remove":
+            out.append("+" + line[1:])
+        elif kind == "add":
+            out.append("-" + line[1:])
+        else:
+            out.append(line)
+    return True, _join_patch_lines(out, patch), ""
+
+
+def _is_patch_reversible_python(patch: str) -> tuple[bool, bool, str]:
+    ok, reversed_text, error = _reverse_patch_python(patch)
+    if not ok:
+        return False, False, error
+    valid, reason = _validate_patch_python(reversed_text)
+    if not valid:
+        return True, False, f"Reversed patch is invalid: {reason}"
+    return True, True, "ok"
+
+
+def _hash_parts_python(parts: list[str], separator: str) -> str:
+    digest = hashlib.sha256()
+    encoded_separator = separator.encode("utf-8")
+    for index, part in enumerate(parts):
+        if index:
+            digest.update(encoded_separator)
+        digest.update(part.encode("utf-8"))
+    return digest.hexdigest()
+
+
+def _read_text_strict_python(path: str) -> tuple[bool, str, str]:
+    try:
+        with open(path, "rb") as handle:
+            data = handle.read()
+    except OSError as e:
+        return False, "", str(e)
+    try:
+        return True, data.decode("utf-8"), ""
+    except UnicodeDecodeError as e:
+        return False, "", f"File is not valid UTF-8 (invalid byte at offset {e.start})"
+
+
+def _build_manifest_python(root: str) -> tuple[bool, list[tuple[str, int, str]], str]:
+    if not os.path.isdir(root):
+        return False, [], f"Not a directory: {root}"
+    try:
+        files = _walk_files_python(root, respect_gitignore=True)
+        sizes = [os.path.getsize(path) for _, path in files]
+        digests = _hash_files_python([path for _, path in files])
+    except OSError as e:
+        return False, [], str(e)
+    return True, [(rel, size, digest) for (rel, _), size, digest in zip(files, sizes, digests)], ""
+
+
+def _execute_command_streaming_python(
+    argv: list[str],
+    cwd: str,
+    timeout_seconds: float,
+    callback: Callable[[str, str], object],
+    line_mode: bool,
+    max_line_bytes: int,
+) -> tuple[bool, int, str]:
+    limit = max(int(max_line_bytes), 1)
+    decoder_factory = codecs.getincrementaldecoder("utf-8")
+    decoders = {stream: decoder_factory(errors="replace") for stream in ("stdout", "stderr")}
+    pending = {"stdout": bytearray(), "stderr": bytearray()}
+    failures: list[str] = []
+
+    def deliver(stream: str, chunk: bytes) -> None:
+        if not line_mode:
+            text = decoders[stream].decode(chunk)
+            if text:
+                callback(stream, text)
+            return
+        buffer = pending[stream]
+        buffer.extend(chunk)
+        while True:
+            newline = buffer.find(b"\n", 0, limit + 1)
+            if newline != -1:
+                piece = bytes(buffer[:newline])
+                del buffer[: newline + 1]
+            elif len(buffer) >= limit:
+                piece = bytes(buffer[:limit])
+                del buffer[:limit]
+            else:
+                break
+            callback(stream, decoders[stream].decode(piece).rstrip("\r"))
+
+    def on_chunk(stream: str, chunk: bytes) -> Optional[bool]:
+        try:
+            deliver(stream, chunk)
+        except Exception as e:
+            failures.append(f"Streaming callback failed: {e}")
+            return True
+        return None
+
+    returncode, timed_out, error = _stream_argv_python(argv, cwd, timeout_seconds, on_chunk)
+    if not failures:
+        try:
+            for stream in ("stdout", "stderr"):
+                tail = decoders[stream].decode(bytes(pending[stream]), final=True)
+                if tail:
+                    callback(stream, tail.rstrip("\r") if line_mode else tail)
+        except Exception as e:
+            failures.append(f"Streaming callback failed: {e}")
+
+    success, code, error = _command_outcome(returncode, timed_out, error, timeout_seconds)
+    if failures:
+        return False, code, failures[0]
+    return success, code, error
+
+
+def _minimize_patch_python(patch: str) -> tuple[bool, str, str]:
+    kept = [line for kind, line in _classify_patch_lines(patch) if kind != "meta"]
+    minimized = _join_patch_lines(kept, patch)
+    ok, reason = _validate_patch_python(minimized)
+    if not ok:
+        return False, "", reason
+    return True, minimized, ""
+
+
+def _line_hashes_python(path: str) -> tuple[bool, list[str], str]:
+    digests: list[str] = []
+    try:
+        with open(path, "rb") as handle:
+            for raw in handle:
+                line = raw[:-1] if raw.endswith(b"\n") else raw
+                if line.endswith(b"\r"):
+                    line = line[:-1]
+                digests.append(hashlib.sha256(line).hexdigest())
+    except OSError as e:
+        return False, [], str(e)
+    return True, digests, ""
+
+
+def _capture_argv_python(
+    argv: list[str],
+    cwd: str,
+    timeout_seconds: float,
+    env: Optional[dict[str, str]] = None,
+) -> tuple[bool, int, str, str, str]:
+    """Synchronous (success, returncode, stdout, stderr, error) capture of a command."""
+    captured = {"stdout": bytearray(), "stderr": bytearray()}
+
+    def on_chunk(stream: str, chunk: bytes) -> None:
+        captured[stream].extend(chunk)
+
+    returncode, timed_out, error = _stream_argv_python(argv, cwd, timeout_seconds, on_chunk, env)
+    success, code, error = _command_outcome(returncode, timed_out, error, timeout_seconds)
+    return (
+        success,
+        code,
+        captured["stdout"].decode("utf-8", errors="replace"),
+        captured["stderr"].decode("utf-8", errors="replace"),
+        error,
+    )
+
+
+def _execute_command_min_runtime_python(
+    argv: list[str],
+    cwd: str,
+    timeout_seconds: float,
+    min_seconds: float,
+) -> tuple[bool, int, str, str, str, float, bool]:
+    started = time.monotonic()
+    success, code, stdout, stderr, error = _capture_argv_python(argv, cwd, timeout_seconds)
+    elapsed = time.monotonic() - started
+    return success, code, stdout, stderr, error, elapsed, elapsed < float(min_seconds)
+
+
+def _tree_digests_python(root: str) -> dict[str, str]:
+    files = _walk_files_python(root, respect_gitignore=True)
+    digests = _hash_files_python([path for _, path in files])
+    return {rel: digest for (rel, _), digest in zip(files, digests)}
+
+
+def _diff_trees_python(
+    a_root: str,
+    b_root: str,
+) -> tuple[bool, list[str], list[str], list[str], str]:
+    for root in (a_root, b_root):
+        if not os.path.isdir(root):
+            return False, [], [], [], f"Not a directory: {root}"
+    try:
+        with ThreadPoolExecutor(max_workers=2) as pool:
+            a_future = pool.submit(_tree_digests_python, a_root)
+            b_future = pool.submit(_tree_digests_python, b_root)
+            a_files, b_files = a_future.result(), b_future.result()
+    except OSError as e:
+        return False, [], [], [], str(e)
+
+    added = sorted(set(b_files) - set(a_files))
+    removed = sorted(set(a_files) - set(b_files))
+    modified = sorted(rel for rel in set(a_files) & set(b_files) if a_files[rel] != b_files[rel])
+    return True, added, removed, modified, ""
+
+
+def _read_base64_python(path: str) -> tuple[bool, str, str]:
+    try:
+        with open(path, "rb") as handle:
+            return True, base64.b64encode(handle.read()).decode("ascii"), ""
+    except OSError as e:
+        return False, "", str(e)
+
+
+def _write_base64_python(path: str, data_b64: str) -> tuple[bool, int, str]:
+    try:
+        data = base64.b64decode(data_b64.encode("ascii"), validate=True)
+    except (binascii.Error, UnicodeEncodeError) as e:
+        return False, 0, f"Invalid base64: {e}"
+    try:
+        with open(path, "wb") as handle:
+            handle.write(data)
+    except OSError as e:
+        return False, 0, str(e)
+    return True, len(data), ""
+
+
+def _count_glob_python(root: str, pattern: str) -> tuple[bool, int, str]:
+    if not os.path.isdir(root):
+        return False, 0, f"Not a directory: {root}"
+    if not pattern.strip():
+        return False, 0, "Glob pattern is empty"
+    matcher = re.compile(_glob_to_regex(pattern.strip().lstrip("/")))
+    try:
+        entries = _iter_entries_python(root, respect_gitignore=False)
+        count = sum(1 for rel, _, _ in entries if matcher.fullmatch(rel))
+    except OSError as e:
+        return False, 0, str(e)
+    return True, count, ""
+
+
+class _BodyLine(NamedTuple):
+    token: str
+    text: str
+    old_pos: int
+    new_pos: int
+    marker: Optional[str]
+
+
+def _collect_patch_segments(
+    patch: str,
+) -> list[Union[str, tuple[re.Match[str], list[tuple[str, str, Optional[str]]]]]]:
+    """Group classified lines into plain header/meta strings and (hunk_match, body) pairs."""
+    segments: list[Union[str, tuple[re.Match[str], list[tuple[str, str, Optional[str]]]]]] = []
+    body: list[tuple[str, str, Optional[str]]] = []
+    for kind, line in _classify_patch_lines(patch):
+        m = _HUNK_RE.match(line) if kind == "hunk" else None
+        if m is not None:
+            body = []
+            segments.append((m, body))
+        elif kind in ("context", "remove", "add"):
+            body.append((line[:1] or " ", line[1:], None))
+        elif kind == "marker":
+            token, text, _ = body[-1]
+            body[-1] = (token, text, line.rstrip())
+        else:
+            segments.append(line.rstrip())
+    return segments
+
+
+def _position_body(
+    m: re.Match[str],
+    raw_body: list[tuple[str, str, Optional[str]]],
+) -> list[_BodyLine]:
+    old_count = int(m.group(2) or "1")
+    new_count = int(m.group(4) or "1")
+    old_cursor = int(m.group(1)) + (0 if old_count else 1)
+    new_cursor = int(m.group(3)) + (0 if new_count else 1)
+    positioned: list[_BodyLine] = []
+    for token, text, marker in raw_body:
+        positioned.append(_BodyLine(token, text, old_cursor, new_cursor, marker))
+        if token in (" ", "-"):
+            old_cursor += 1
+        if token in (" ", "+"):
+            new_cursor += 1
+    return positioned
+
+
+def _hunk_section(m: re.Match[str]) -> str:
+    section = m.string[m.end() :].strip()
+    return f" {section}" if section else ""
+
+
+def _emit_hunk(body: list[_BodyLine], section: str) -> list[str]:
+    old_count = sum(1 for entry in body if entry.token in (" ", "-"))
+    new_count = sum(1 for entry in body if entry.token in (" ", "+"))
+    old_start = body[0].old_pos if old_count else body[0].old_pos - 1
+    new_start = body[0].new_pos if new_count else body[0].new_pos - 1
+    out = [f"@@ -{old_start},{old_count} +{new_start},{new_count} @@{section}"]
+    for entry in body:
+        out.append(entry.token + entry.text)
+        if entry.marker:
+            out.append(entry.marker)
+    return out
+
+
+def _window_hunk(body: list[_BodyLine], context: int) -> list[list[_BodyLine]]:
+    changes = [index for index, entry in enumerate(body) if entry.token != " "]
+    if not changes:
+        return []
+    groups: list[list[int]] = [[changes[0]]]
+    for index in changes[1:]:
+        if index - groups[-1][-1] - 1 > 2 * context:
+            groups.append([index])
+        else:
+            groups[-1].append(index)
+    return [body[max(group[0] - context, 0) : group[-1] + context + 1] for group in groups]
+
+
+def _format_patch_python(patch: str, context: Optional[int]) -> tuple[bool, str, str]:
+    ok, reason = _validate_patch_python(patch)
+    if not ok:
+        return False, "", reason
+    if context is not None and context < 0:
+        return False, "", "context must be non-negative"
+
+    out: list[str] = []
+    for segment in _collect_patch_segments(patch):
+        if isinstance(segment, str):
+            out.append(segment)
+            continue
+        m, raw_body = segment
+        section = _hunk_section(m)
+        body = _position_body(m, raw_body)
+        pieces = [body] if context is None else _window_hunk(body, context)
+        for index, piece in enumerate(pieces):
+            if piece:
+                out.extend(_emit_hunk(piece, section if index == 0 else ""))
+    return True, _join_patch_lines(out, patch), ""
+
+
+def _spawn_detached_python(
+    argv: list[str],
+    cwd: str,
+    log_path: Optional[str],
+) -> tuple[bool, int, str]:
+    if not argv:
+        return False, 0, "Empty command"
+    kwargs: dict[str, object] = {}
+    if os.name == "nt":
+        kwargs["creationflags"] = subprocess.DETACHED_PROCESS | subprocess.CREATE_NEW_PROCESS_GROUP
+    else:
+        kwargs["start_new_session"] = True
+    try:
+        output = open(log_path, "ab") if log_path else open(os.devnull, "wb")
+    except OSError as e:
+        return False, 0, str(e)
+    try:
+        with output:
+            process = subprocess.Popen(
+                argv,
+                cwd=cwd or None,
+                stdin=subprocess.DEVNULL,
+                stdout=output,
+                stderr=subprocess.STDOUT,
+                close_fds=True,
+                **kwargs,  # type: ignore[arg-type]
+            )
+    except (OSError, ValueError) as e:
+        return False, 0, str(e)
+    _track_handle(process)
+
+    # Reap the child whenever it exits so it never lingers as a zombie.
+    def reap() -> None:
+        process.wait()
+        _untrack_handle(process)
+
+    threading.Thread(target=reap, daemon=True).start()
+    return True, int(process.pid), ""
+
+
+def _read_lines_lossy(path: str) -> list[str]:
+    with open(path, "rb") as handle:
+        return handle.read().decode("utf-8", errors="replace").splitlines()
+
+
+def _hunk_old_lines(hunk: _Hunk) -> list[str]:
+    return [text for token, text in hunk.lines if token in (" ", "-")]
+
+
+def _hunk_matches_at(lines: list[str], hunk: _Hunk, start: int) -> bool:
+    expected = _hunk_old_lines(hunk)
+    if start < 0 or start + len(expected) > len(lines):
+        return False
+    return lines[start : start + len(expected)] == expected
+
+
+def _hunks_status_python(path: str, patch: str) -> tuple[bool, list[tuple[int, bool]], str]:
+    ok, reason = _validate_patch_python(patch)
+    if not ok:
+        return False, [], reason
+    try:
+        lines = _read_lines_lossy(path)
+    except OSError as e:
+        return False, [], str(e)
+
+    statuses = []
+    for index, hunk in enumerate(_parse_hunks_python(patch)):
+        start = hunk.old_start - 1 if hunk.old_count else hunk.old_start
+        statuses.append((index, _hunk_matches_at(lines, hunk, start)))
+    return True, statuses, ""
+
+
+def _read_text_bom_python(path: str) -> tuple[bool, str, bool, str]:
+    try:
+        with open(path, "rb") as handle:
+            data = handle.read()
+    except OSError as e:
+        return False, "", False, str(e)
+    had_bom = data.startswith(codecs.BOM_UTF8)
+    if had_bom:
+        data = data[len(codecs.BOM_UTF8) :]
+    return True, data.decode("utf-8", errors="replace"), had_bom, ""
+
+
+def _looks_like_path_arg(arg: str) -> bool:
+    if not arg or arg.startswith("-") or "=" in arg or "://" in arg:
+        return False
+    if any(ch in arg for ch in "*?[{$"):
+        return False
+    return "/" in arg or (os.sep != "/" and os.sep in arg)
+
+
+def _check_argv_paths_python(argv: list[str], cwd: str) -> tuple[bool, list[str], str]:
+    missing = [
+        arg
+        for arg in argv[1:]
+        if _looks_like_path_arg(arg) and not os.path.exists(os.path.join(cwd or ".", arg))
+    ]
+    if missing:
+        return False, missing, f"{len(missing)} path argument(s) not found"
+    return True, [], ""
+
+
+_SHA256_K = (
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+    0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+    0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+    0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+    0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+    0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+    0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+    0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+)
+_SHA256_IV = (
+    0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+)
+
+
+class _ResumableSha256:
+    """Pure-Python SHA-256 whose intermediate state can be serialized (hashlib's can't)."""
+
+    def __init__(self, state: bytes = b"") -> None:
+        if not state:
+            self._h = list(_SHA256_IV)
+            self._length = 0
+            self._tail = b""
+            return
+        if len(state) < 40 or len(state) >= 104:
+            raise ValueError("Invalid SHA-256 state")
+        self._h = list(struct.unpack(">8I", state[:32]))
+        self._length = struct.unpack(">Q", state[32:40])[0]
+        self._tail = bytes(state[40:])
+        if self._length % 64 != len(self._tail):
+            raise ValueError("Invalid SHA-256 state")
+
+    @property
+    def length(self) -> int:
+        return self._length
+
+    def update(self, data: bytes) -> None:
+        self._length += len(data)
+        data = self._tail + data
+        whole = len(data) - len(data) % 64
+        for offset in range(0, whole, 64):
+            self._compress(data[offset : offset + 64])
+        self._tail = data[whole:]
+
+    def state(self) -> bytes:
+        return struct.pack(">8I", *self._h) + struct.pack(">Q", self._length) + self._tail
+
+    def hexdigest(self) -> str:
+        clone = _ResumableSha256(self.state())
+        padding = b"\x80" + b"\x00" * ((55 - self._length) % 64)
+        clone.update(padding + struct.pack(">Q", self._length * 8))
+        return struct.pack(">8I", *clone._h).hex()
+
+    def _compress(self, block: bytes) -> None:
+        w = list(struct.unpack(">16I", block))
+        for i in range(16, 64):
+            s0 = _rotr32(w[i - 15], 7) ^ _rotr32(w[i - 15], 18) ^ (w[i - 15] >> 3)
+            s1 = _rotr32(w[i - 2], 17) ^ _rotr32(w[i - 2], 19) ^ (w[i - 2] >> 10)
+            w.append((w[i - 16] + s0 + w[i - 7] + s1) & 0xFFFFFFFF)
+        a, b, c, d, e, f, g, h = self._h
+        for i in range(64):
+            t1 = h + (_rotr32(e, 6) ^ _rotr32(e, 11) ^ _rotr32(e, 25)) + ((e & f) ^ (~e & g))
+            t1 = (t1 + _SHA256_K[i] + w[i]) & 0xFFFFFFFF
+            t2 = (_rotr32(a, 2) ^ _rotr32(a, 13) ^ _rotr32(a, 22)) + ((a & b) ^ (a & c) ^ (b & c))
+            h, g, f, e = g, f, e, (d + t1) & 0xFFFFFFFF
+            d, c, b, a = c, b, a, (t1 + t2) & 0xFFFFFFFF
+        self._h = [(x + y) & 0xFFFFFFFF for x, y in zip(self._h, (a, b, c, d, e, f, g, h))]
+
+
+def _rotr32(value: int, bits: int) -> int:
+    return ((value >> bits) | (value << (32 - bits))) & 0xFFFFFFFF
+
+
+def _hash_with_previous_python(
+    path: str,
+    previous_hash_state: bytes,
+    from_offset: int,
+) -> tuple[bool, str, bytes, str]:
+    try:
+        hasher = _ResumableSha256(bytes(previous_hash_state))
+    except ValueError as e:
+        return False, "", b"", str(e)
+    if hasher.length != from_offset:
+        error = f"State covers {hasher.length} bytes but from_offset is {from_offset}"
+        return False, "", b"", error
+    try:
+        with open(path, "rb") as handle:
+            handle.seek(from_offset)
+            for chunk in iter(lambda: handle.read(_READ_CHUNK_BYTES), b""):
+                hasher.update(chunk)
+    except OSError as e:
+        return False, "", b"", str(e)
+    return True, hasher.hexdigest(), hasher.state(), ""
+
+
+def _execute_hermetic_python(
+    argv: list[str],
+    cwd: str,
+    timeout_seconds: float,
+    path_dirs: list[str],
+    extra_env: list[tuple[str, str]],
+) -> tuple[bool, int, str, str, str]:
+    env = {"PATH": os.pathsep.join(str(d) for d in path_dirs)}
+    env.update({str(key): str(value) for key, value in extra_env})
+    return _capture_argv_python(argv, cwd, timeout_seconds, env)
+
+
+def _git_blob_hash_python(path: str) -> tuple[bool, str, str]:
+    try:
+        with open(path, "rb") as handle:
+            size = os.fstat(handle.fileno()).st_size
+            digest = hashlib.sha1(f"blob {size}\0".encode("ascii"))
+            read = 0
+            for chunk in iter(lambda: handle.read(_READ_CHUNK_BYTES), b""):
+                digest.update(chunk)
+                read += len(chunk)
+    except OSError as e:
+        return False, "", str(e)
+    if read != size:
+        return False, "", "File changed size while hashing"
+    return True, digest.hexdigest(), ""
+
+
+_WORD_RE = re.compile(r"[^\W\d]\w*")
+
+
+def _tokenize_words_python(content: str) -> list[tuple[str, int, int]]:
+    tokens: list[tuple[str, int, int]] = []
+    char_pos = 0
+    byte_pos = 0
+    for m in _WORD_RE.finditer(content):
+        byte_pos += len(content[char_pos : m.start()].encode("utf-8"))
+        word = m.group(0)
+        end = byte_pos + len(word.encode("utf-8"))
+        tokens.append((word, byte_pos, end))
+        byte_pos = end
+        char_pos = m.end()
+    return tokens
+
+
+def _secure_delete_python(path: str) -> tuple[bool, str]:
+    if os.path.islink(path) or not os.path.isfile(path):
+        return False, f"Not a regular file: {path}"
+    try:
+        with open(path, "r+b") as handle:
+            remaining = os.fstat(handle.fileno()).st_size
+            zeros = bytes(_READ_CHUNK_BYTES)
+            while remaining > 0:
+                step = min(remaining, len(zeros))
+                handle.write(zeros[:step])
+                remaining -= step
+            handle.flush()
+            os.fsync(handle.fileno())
+        os.remove(path)
+    except OSError as e:
+        return False, str(e)
+    return True, ""
+
+
+def _detect_moves_python(
+    old: str,
+    new: str,
+    min_block_lines: int,
+) -> tuple[bool, list[tuple[int, int, int]], str]:
+    if min_block_lines < 1:
+        return False, [], "min_block_lines must be >= 1"
+    old_lines = old.splitlines()
+    new_lines = new.splitlines()
+
+    deleted = [False] * len(old_lines)
+    inserted = [False] * len(new_lines)
+    matcher = difflib.SequenceMatcher(None, old_lines, new_lines, autojunk=False)
+    for tag, i1, i2, j1, j2 in matcher.get_opcodes():
+        if tag in ("delete", "replace"):
+            deleted[i1:i2] = [True] * (i2 - i1)
+        if tag in ("insert", "replace"):
+            inserted[j1:j2] = [True] * (j2 - j1)
+
+    candidates: dict[str, list[int]] = {}
+    for j, line in enumerate(new_lines):
+        if inserted[j]:
+            candidates.setdefault(line, []).append(j)
+
+    blocks: list[tuple[int, int, int]] = []
+    i = 0
+    while i < len(old_lines):
+        best_j, best_len = -1, 0
+        if deleted[i]:
+            for j in candidates.get(old_lines[i], []):
+                length = 0
+                while (
+                    i + length < len(old_lines)
+                    and j + length < len(new_lines)
+                    and deleted[i + length]
+                    and inserted[j + length]
+                    and old_lines[i + length] == new_lines[j + length]
+                ):
+                    length += 1
+                if length > best_len:
+                    best_j, best_len = j, length
+        if best_len >= min_block_lines:
+            blocks.append((i + 1, best_j + 1, best_len))
+            inserted[best_j : best_j + best_len] = [False] * best_len
+            i += best_len
+        else:
+            i += 1
+    return True, blocks, ""
+
+
+_TRANSIENT_ERRNOS = {errno.EINTR, errno.EAGAIN, errno.EWOULDBLOCK, errno.ETIMEDOUT}
+
+
+def _is_transient_os_error(error: OSError) -> bool:
+    if isinstance(error, (InterruptedError, BlockingIOError, TimeoutError)):
+        return True
+    return error.errno in _TRANSIENT_ERRNOS
+
+
+def _read_text_retry_python(path: str, max_retries: int, backoff_ms: int) -> tuple[bool, str, str]:
+    attempt = 0
+    while True:
+        try:
+            with open(path, "rb") as handle:
+                return True, handle.read().decode("utf-8", errors="replace"), ""
+        except OSError as e:
+            if not _is_transient_os_error(e) or attempt >= max_retries:
+                suffix = f" (after {attempt} retries)" if attempt else ""
+                return False, "", f"{e}{suffix}"
+            time.sleep(max(backoff_ms, 0) * (2**attempt) / 1000.0)
+            attempt += 1
+
+
+def _list_dir_detailed_python(
+    path: str,
+) -> tuple[bool, list[tuple[str, bool, int, float, bool]], str]:
+    entries: list[tuple[str, bool, int, float, bool]] = []
+    try:
+        with os.scandir(path) as it:
+            for entry in it:
+                info = entry.stat(follow_symlinks=False)
+                entries.append(
+                    (
+                        entry.name,
+                        stat.S_ISDIR(info.st_mode),
+                        int(info.st_size),
+                        float(info.st_mtime),
+                        stat.S_ISLNK(info.st_mode),
+                    )
+                )
+    except OSError as e:
+        return False, [], str(e)
+    entries.sort(key=lambda item: item[0])
+    return True, entries, ""
+
+
+def _replace_unique(content: str, old_string: str, new_string: str) -> tuple[Optional[str], str]:
+    if not old_string:
+        return None, "old_string must not be empty"
+    count = content.count(old_string)
+    if count == 0:
+        return None, "old_string not found"
+    if count > 1:
+        return None, f"old_string is not unique: found {count} occurrences"
+    return content.replace(old_string, new_string, 1), ""
+
+
+def _read_utf8_for_edit(path: str) -> tuple[Optional[str], str]:
+    try:
+        with open(path, "rb") as handle:
+            return handle.read().decode("utf-8"), ""
+    except OSError as e:
+        return None, str(e)
+    except UnicodeDecodeError:
+        return None, "File is not valid UTF-8"
+
+
+def _apply_string_edit_python(path: str, old_string: str, new_string: str) -> tuple[bool, str]:
+    content, error = _read_utf8_for_edit(path)
+    if content is None:
+        return False, error
+    updated, error = _replace_unique(content, old_string, new_string)
+    if updated is None:
+        return False, error
+    try:
+        _atomic_write_bytes(path, updated.encode("utf-8"))
+    except OSError as e:
+        return False, str(e)
+    return True, ""
+
+
+def _count_occurrences_in_file_python(
+    path: str,
+    needle: str,
+    chunk_size: int = _READ_CHUNK_BYTES,
+) -> tuple[bool, int, str]:
+    pattern = needle.encode("utf-8")
+    if not pattern:
+        return False, 0, "needle must not be empty"
+    count = 0
+    carry = b""
+    try:
+        with open(path, "rb") as handle:
+            for chunk in iter(lambda: handle.read(chunk_size), b""):
+                buffer = carry + chunk
+                pos = 0
+                while True:
+                    found = buffer.find(pattern, pos)
+                    if found == -1:
+                        break
+                    count += 1
+                    pos = found + len(pattern)
+                carry = buffer[max(pos, len(buffer) - len(pattern) + 1) :]
+    except OSError as e:
+        return False, 0, str(e)
+    return True, count, ""
+
+
+def _apply_edits_to_content(
+    content: str,
+    edits: list[tuple[str, str]],
+) -> tuple[Optional[str], int, str]:
+    for index, (old_string, new_string) in enumerate(edits):
+        updated, error = _replace_unique(content, old_string, new_string)
+        if updated is None:
+            return None, index, f"edit {index}: {error}"
+        content = updated
+    return content, len(edits), ""
+
+
+def _apply_string_edits_python(
+    path: str,
+    edits: list[tuple[str, str]],
+) -> tuple[bool, int, str]:
+    content, error = _read_utf8_for_edit(path)
+    if content is None:
+        return False, 0, error
+    updated, applied, error = _apply_edits_to_content(content, edits)
+    if updated is None:
+        return False, applied, error
+    try:
+        _atomic_write_bytes(path, updated.encode("utf-8"))
+    except OSError as e:
+        return False, 0, str(e)
+    return True, applied, ""
+
+
+def _unified_diff_text(old: str, new: str, old_name: str, new_name: str, context: int) -> str:
+    out = []
+    for line in difflib.unified_diff(
+        old.splitlines(keepends=True),
+        new.splitlines(keepends=True),
+        fromfile=old_name,
+        tofile=new_name,
+        n=max(0, context),
+    ):
+        if line.endswith("\n"):
+            out.append(line)
+        else:
+            out.append(line + "\n\\ No newline at end of file\n")
+    return "".join(out)
+
+
+def _string_edits_to_patch_python(
+    path: str,
+    edits: list[tuple[str, str]],
+    context: int,
+) -> tuple[bool, str, str]:
+    content, error = _read_utf8_for_edit(path)
+    if content is None:
+        return False, "", error
+    updated, _, error = _apply_edits_to_content(content, edits)
+    if updated is None:
+        return False, "", error
+    name = str(path)
+    return True, _unified_diff_text(content, updated, f"a/{name}", f"b/{name}", context), ""
+
+
+def _mtime_or_zero(path: str) -> float:
+    try:
+        return os.stat(path).st_mtime
+    except FileNotFoundError:
+        return 0.0
+
+
+def _latest_mtime_python(root: str) -> tuple[bool, float, str]:
+    if not os.path.isdir(root):
+        return False, 0.0, f"Not a directory: {root}"
+    try:
+        paths = [path for _, path in _walk_files_python(root)]
+        with ThreadPoolExecutor() as pool:
+            return True, max(pool.map(_mtime_or_zero, paths), default=0.0), ""
+    except OSError as e:
+        return False, 0.0, str(e)
+
+
+def _execute_command_to_queue_python(
+    argv: list[str],
+    cwd: str,
+    timeout_seconds: float,
+    queue: object,
+) -> tuple[bool, int, str]:
+    put = queue.put  # type: ignore[attr-defined]
+    decoder_factory = codecs.getincrementaldecoder("utf-8")
+    decoders = {stream: decoder_factory(errors="replace") for stream in ("stdout", "stderr")}
+    failures: list[str] = []
+
+    def on_chunk(stream: str, chunk: bytes) -> Optional[bool]:
+        try:
+            text = decoders[stream].decode(chunk)
+            if text:
+                put((stream, text))
+        except Exception as e:
+            failures.append(f"Queue delivery failed: {e}")
+            return True
+        return None
+
+    try:
+        returncode, timed_out, error = _stream_argv_python(argv, cwd, timeout_seconds, on_chunk)
+        if not failures:
+            try:
+                for stream in ("stdout", "stderr"):
+                    tail = decoders[stream].decode(b"", final=True)
+                    if tail:
+                        put((stream, tail))
+            except Exception as e:
+                failures.append(f"Queue delivery failed: {e}")
+    finally:
+        put(None)
+    if failures:
+        return False, -1, failures[0]
+    return _command_outcome(returncode, timed_out, error, timeout_seconds)
+
+
+def _validate_patch_against_file_python(path: str, patch: str) -> tuple[bool, str]:
+    ok, reason = _validate_patch_python(patch)
+    if not ok:
+        return False, reason
+    try:
+        lines = _read_lines_lossy(path)
+    except OSError as e:
+        return False, str(e)
+
+    for number, hunk in enumerate(_parse_hunks_python(patch), start=1):
+        start = hunk.old_start - 1 if hunk.old_count else hunk.old_start
+        expected = _hunk_old_lines(hunk)
+        if start < 0 or start + len(expected) > len(lines):
+            return False, (
+                f"Hunk {number} needs lines {start + 1}-{start + len(expected)} "
+                f"but file has {len(lines)} lines"
+            )
+        for offset, text in enumerate(expected):
+            if lines[start + offset] != text:
+                return False, f"Hunk {number} context mismatch at line {start + offset + 1}"
+    return True, "ok"
+
+
+def _truncate_to_last_bytes_python(path: str, max_bytes: int) -> tuple[bool, int, str]:
+    limit = max(int(max_bytes), 0)
+    try:
+        size = os.path.getsize(path)
+        if size <= limit:
+            return True, size, ""
+        with open(path, "rb") as handle:
+            # One extra byte tells us whether the tail already starts on a fresh line.
+            handle.seek(size - limit - 1)
+            window = handle.read(limit + 1)
+        tail = window[1:]
+        if window[:1] != b"\n":
+            newline = tail.find(b"\n")
+            if newline != -1:
+                tail = tail[newline + 1 :]
+        _atomic_write_bytes(path, tail)
+    except OSError as e:
+        return False, 0, str(e)
+    return True, len(tail), ""
+
+
+def _display_width(ch: str) -> int:
+    if unicodedata.combining(ch):
+        return 0
+    return 2 if unicodedata.east_asian_width(ch) in ("W", "F") else 1
+
+
+def _expand_tabs(text: str, tab_width: int) -> str:
+    width = max(int(tab_width), 1)
+    out = []
+    column = 0
+    for ch in text:
+        if ch == "\t":
+            pad = width - column % width
+            out.append(" " * pad)
+            column += pad
+        elif ch in "\r\n":
+            out.append(ch)
+            column = 0
+        else:
+            out.append(ch)
+            column += _display_width(ch)
+    return "".join(out)
+
+
+def _read_expanded_python(path: str, tab_width: int) -> tuple[bool, str, str]:
+    try:
+        with open(path, "rb") as handle:
+            text = handle.read().decode("utf-8", errors="replace")
+    except OSError as e:
+        return False, "", str(e)
+    return True, _expand_tabs(text, tab_width), ""
+
+
+def _diff_ops_python(old: str, new: str) -> list[tuple[str, int, int, str]]:
+    old_lines = old.splitlines(keepends=True)
+    new_lines = new.splitlines(keepends=True)
+    matcher = difflib.SequenceMatcher(None, old_lines, new_lines, autojunk=False)
+    ops: list[tuple[str, int, int, str]] = []
+    for tag, i1, i2, j1, j2 in matcher.get_opcodes():
+        if tag == "equal":
+            continue
+        text = "".join(new_lines[j1:j2]) if tag != "delete" else ""
+        ops.append((tag, i1 + 1, i2 - i1, text))
+    return ops
+
+
+def _read_snapshot_python(path: str, max_retries: int) -> tuple[bool, str, int, str]:
+    attempts = max(int(max_retries), 0) + 1
+    try:
+        with open(path, "rb") as handle:
+            for _ in range(attempts):
+                before = os.fstat(handle.fileno()).st_size
+                handle.seek(0)
+                data = handle.read(before)
+                after = os.fstat(handle.fileno()).st_size
+                if before == after and len(data) == before:
+                    return True, data.decode("utf-8", errors="replace"), before, ""
+    except OSError as e:
+        return False, "", 0, str(e)
+    return False, "", 0, f"File kept changing size after {attempts} attempts"
+
+
+def _offset_to_position_python(content: str, offset: int) -> tuple[bool, int, int, str]:
+    data = content.encode("utf-8")
+    if offset < 0 or offset > len(data):
+        return False, 0, 0, f"Offset {offset} out of range (0..{len(data)})"
+    if offset < len(data) and (data[offset] & 0xC0) == 0x80:
+        return False, 0, 0, f"Offset {offset} is not on a character boundary"
+    before = data[:offset]
+    line_start = before.rfind(b"\n") + 1
+    line = before.count(b"\n") + 1
+    column = len(before[line_start:].decode("utf-8")) + 1
+    return True, line, column, ""
+
+
+def _position_to_offset_python(content: str, line: int, column: int) -> tuple[bool, int, str]:
+    if line < 1 or column < 1:
+        return False, 0, "line and column are 1-based"
+    lines = content.split("\n")
+    if line > len(lines):
+        return False, 0, f"Line {line} out of range (1..{len(lines)})"
+    offset = sum(len(text.encode("utf-8")) + 1 for text in lines[: line - 1])
+    text = lines[line - 1]
+    if line < len(lines) and text.endswith("\r"):
+        text = text[:-1]
+    return True, offset + len(text[: column - 1].encode("utf-8")), ""
+
+
+def _ends_with_newline_python(path: str) -> tuple[bool, bool, str]:
+    try:
+        with open(path, "rb") as handle:
+            handle.seek(0, os.SEEK_END)
+            if handle.tell() == 0:
+                return True, False, ""
+            handle.seek(-1, os.SEEK_END)
+            return True, handle.read(1) == b"\n", ""
+    except OSError as e:
+        return False, False, str(e)
+
+
+def _execute_command_status_python(
+    argv: list[str],
+    cwd: str,
+    timeout_seconds: float,
+) -> tuple[str, int, int, str, str]:
+    captured = {"stdout": bytearray(), "stderr": bytearray()}
+
+    def on_chunk(stream: str, chunk: bytes) -> None:
+        captured[stream].extend(chunk)
+
+    returncode, timed_out, error = _stream_argv_python(argv, cwd, timeout_seconds, on_chunk)
+    stdout = captured["stdout"].decode("utf-8", errors="replace")
+    stderr = captured["stderr"].decode("utf-8", errors="replace")
+    if error:
+        return "spawn_error", -1, 0, "", error
+    if timed_out:
+        return "timeout", -1, 0, stdout, stderr
+    code = int(returncode if returncode is not None else -1)
+    if code < 0:
+        return "signal", -1, -code, stdout, stderr
+    return ("ok" if code == 0 else "failed"), code, 0, stdout, stderr
+
+
+def _find_symlinks_python(root: str) -> tuple[bool, list[tuple[str, str]], str]:
+    if not os.path.isdir(root):
+        return False, [], f"Not a directory: {root}"
+    links = []
+    try:
+        for rel, path, _ in _iter_entries_python(root):
+            if os.path.islink(path):
+                links.append((rel, os.readlink(path)))
+    except OSError as e:
+        return False, [], str(e)
+    return True, sorted(links), ""
+
+
+def _apply_patch_python(
+    original: str,
+    patch: str,
+    ignore_trailing_ws: bool = False,
+) -> tuple[Optional[str], str]:
+    """Apply a single-file unified diff strictly at its stated positions."""
+    ok, reason = _validate_patch_python(patch)
+    if not ok:
+        return None, reason
+
+    hunks: list[tuple[int, int, list[list]]] = []
+    for kind, line in _classify_patch_lines(patch):
+        m = _HUNK_RE.match(line) if kind == "hunk" else None
+        if m:
+            hunks.append((int(m.group(1)), int(m.group(2) or "1"), []))
+        elif kind in ("context", "remove", "add") and hunks:
+            hunks[-1][2].append([kind, line[1:], True])
+        elif kind == "marker" and hunks and hunks[-1][2]:
+            hunks[-1][2][-1][2] = False
+
+    source = original.splitlines(keepends=True)
+    out: list[str] = []
+    cursor = 0
+    for number, (old_start, old_count, body) in enumerate(hunks, start=1):
+        start = old_start - 1 if old_count else old_start
+        if start < cursor:
+            return None, f"Hunk {number} overlaps the previous hunk"
+        out.extend(source[cursor:start])
+        position = start
+        for kind, text, _ in body:
+            if kind == "add":
+                continue
+            if position >= len(source):
+                return None, f"Hunk {number} does not apply at line {position + 1}"
+            actual = source[position].rstrip("\r\n")
+            if ignore_trailing_ws:
+                actual, text = actual.rstrip(), text.rstrip()
+            if actual != text:
+                return None, f"Hunk {number} does not apply at line {position + 1}"
+            position += 1
+        position = start
+        for kind, text, newline in body:
+            if kind == "context":
+                out.append(source[position])
+                position += 1
+            elif kind == "remove":
+                position += 1
+            else:
+                out.append(text + "\n" if newline else text)
+        cursor = position
+    out.extend(source[cursor:])
+    return "".join(out), ""
+
+
+def _preview_patch_on_file_python(path: str, patch: str, context: int) -> tuple[bool, str, str]:
+    before, error = _read_utf8_for_edit(path)
+    if before is None:
+        return False, "", error
+    after, error = _apply_patch_python(before, patch)
+    if after is None:
+        return False, "", error
+    name = str(path)
+    return True, _unified_diff_text(before, after, f"a/{name}", f"b/{name}", context), ""
+
+
+def _changed_regions(base: list[str], other: list[str]) -> list[tuple[int, int, list[str]]]:
+    matcher = difflib.SequenceMatcher(None, base, other, autojunk=False)
+    return [
+        (i1, i2, other[j1:j2])
+        for tag, i1, i2, j1, j2 in matcher.get_opcodes()
+        if tag != "equal"
+    ]
+
+
+def _apply_regions(
+    base: list[str],
+    start: int,
+    end: int,
+    regions: list[tuple[int, int, list[str]]],
+) -> list[str]:
+    out: list[str] = []
+    cursor = start
+    for i1, i2, replacement in regions:
+        out.extend(base[cursor:i1])
+        out.extend(replacement)
+        cursor = i2
+    out.extend(base[cursor:end])
+    return out
+
+
+def _terminated(lines: list[str]) -> list[str]:
+    if lines and not lines[-1].endswith("\n"):
+        return lines[:-1] + [lines[-1] + "\n"]
+    return lines
+
+
+def _merge3_python(base: str, ours: str, theirs: str) -> tuple[str, int]:
+    base_lines = base.splitlines(keepends=True)
+    changes = sorted(
+        (i1, i2, side, lines)
+        for side, other in enumerate((ours, theirs))
+        for i1, i2, lines in _changed_regions(base_lines, other.splitlines(keepends=True))
+    )
+
+    out: list[str] = []
+    conflicts = 0
+    cursor = 0
+    index = 0
+    while index < len(changes):
+        start, end = changes[index][0], changes[index][1]
+        cluster = [changes[index]]
+        index += 1
+        # Overlapping regions, or an insertion touching another change, merge into one cluster.
+        while index < len(changes):
+            i1, i2 = changes[index][0], changes[index][1]
+            if i1 < end or (i1 == end and (i1 == i2 or start == end)):
+                cluster.append(changes[index])
+                end = max(end, i2)
+                index += 1
+            else:
+                break
+
+        out.extend(base_lines[cursor:start])
+        sides = []
+        for side in (0, 1):
+            regions = [(i1, i2, lines) for i1, i2, s, lines in cluster if s == side]
+            sides.append(_apply_regions(base_lines, start, end, regions) if regions else None)
+        ours_part, theirs_part = sides
+        if theirs_part is None or ours_part == theirs_part:
+            out.extend(ours_part or [])
+        elif ours_part is None:
+            out.extend(theirs_part)
+        else:
+            conflicts += 1
+            out.append("<<<<<<< ours\n")
+            out.extend(_terminated(ours_part))
+            out.append("||||||| base\n")
+            out.extend(_terminated(base_lines[start:end]))
+            out.append("=======\n")
+            out.extend(_terminated(theirs_part))
+            out.append(">>>>>>> theirs\n")
+        cursor = end
+    out.extend(base_lines[cursor:])
+    return "".join(out), conflicts
+
+
+def _execute_parallel_python(
+    commands: list[list[str]],
+    cwd: str,
+    timeout_seconds: float,
+    max_concurrency: int,
+) -> list[tuple[bool, int, str, str, str]]:
+    if not commands:
+        return []
+    workers = max(1, min(int(max_concurrency), len(commands)))
+    with ThreadPoolExecutor(max_workers=workers) as pool:
+        return list(
+            pool.map(lambda argv: _capture_argv_python(argv, cwd, timeout_seconds), commands)
+        )
+
+
+_LANGUAGE_BY_EXTENSION = {
+    ".py": "python",
+    ".pyi": "python",
+    ".rs": "rust",
+    ".js": "javascript",
+    ".mjs": "javascript",
+    ".cjs": "javascript",
+    ".jsx": "javascript",
+    ".ts": "typescript",
+    ".tsx": "typescript",
+    ".go": "go",
+    ".java": "java",
+    ".c": "c",
+    ".h": "c",
+    ".cpp": "cpp",
+    ".cc": "cpp",
+    ".hpp": "cpp",
+    ".rb": "ruby",
+    ".sh": "shell",
+    ".bash": "shell",
+    ".md": "markdown",
+    ".json": "json",
+    ".yaml": "yaml",
+    ".yml": "yaml",
+    ".toml": "toml",
+    ".ini": "ini",
+    ".html": "html",
+    ".css": "css",
+    ".sql": "sql",
+}
+
+_LANGUAGE_BY_FILENAME = {
+    "makefile": "make",
+    "dockerfile": "dockerfile",
+    "cargo.lock": "toml",
+}
+
+_LANGUAGE_BY_INTERPRETER = {
+    "python": "python",
+    "sh": "shell",
+    "bash": "shell",
+    "zsh": "shell",
+    "node": "javascript",
+    "ruby": "ruby",
+    "perl": "perl",
+}
+
+
+def _detect_language_python(path: str, content: str) -> str:
+    name = os.path.basename(path).lower()
+    if name in _LANGUAGE_BY_FILENAME:
+        return _LANGUAGE_BY_FILENAME[name]
+    language = _LANGUAGE_BY_EXTENSION.get(os.path.splitext(name)[1])
+    if language:
+        return language
+    if content.startswith("#!"):
+        words = content[2:].split("\n", 1)[0].split()
+        if words and os.path.basename(words[0]) == "env" and len(words) > 1:
+            words = words[1:]
+        if words:
+            interpreter = re.sub(r"[\d.]+$", "", os.path.basename(words[0]))
+            if interpreter in _LANGUAGE_BY_INTERPRETER:
+                return _LANGUAGE_BY_INTERPRETER[interpreter]
+    return "text"
+
+
+def _read_with_language_python(path: str) -> tuple[bool, str, str, str]:
+    content, error = _read_utf8_for_edit(path)
+    if content is None:
+        return False, "", "", error
+    return True, content, _detect_language_python(path, content), ""
+
+
+def _load_tree_gitignore(root: str) -> _GitignoreRules:
+    rules = _GitignoreRules()
+    found = [
+        rel
+        for rel, _ in _walk_files_python(root, respect_gitignore=False)
+        if rel == ".gitignore" or rel.endswith("/.gitignore")
+    ]
+    for rel in sorted(found, key=lambda rel: rel.count("/")):
+        rules.load(rel.rpartition("/")[0], os.path.join(root, rel))
+    return rules
+
+
+def _path_is_ignored(rules: _GitignoreRules, rel: str) -> bool:
+    parts = rel.split("/")
+    for depth in range(1, len(parts)):
+        if rules.is_ignored("/".join(parts[:depth]), True):
+            return True
+    return rules.is_ignored(rel, False)
+
+
+def _verify_manifest_python(
+    root: str,
+    manifest: list[tuple[str, int, str]],
+    respect_gitignore: bool,
+) -> tuple[bool, list[str], list[str], list[str], str]:
+    if not os.path.isdir(root):
+        return False, [], [], [], f"Not a directory: {root}"
+    try:
+        on_disk = dict(_walk_files_python(root, respect_gitignore))
+        rules = _load_tree_gitignore(root) if respect_gitignore else None
+
+        missing: list[str] = []
+        mismatched: list[str] = []
+        to_hash: list[tuple[str, str]] = []
+        expected: dict[str, str] = {}
+        for rel, size, digest in manifest:
+            if rules is not None and _path_is_ignored(rules, rel):
+                continue
+            expected[rel] = digest
+            path = on_disk.get(rel)
+            if path is None:
+                missing.append(rel)
+            elif os.path.getsize(path) != size:
+                mismatched.append(rel)
+            else:
+                to_hash.append((rel, path))
+
+        digests = _hash_files_python([path for _, path in to_hash])
+        mismatched.extend(rel for (rel, _), got in zip(to_hash, digests) if got != expected[rel])
+        extra = [rel for rel in on_disk if rel not in expected]
+    except OSError as e:
+        return False, [], [], [], str(e)
+    return True, sorted(missing), sorted(mismatched), sorted(extra), ""
+
+
+def _read_without_comments_python(
+    path: str,
+    comment_prefix: str,
+    keep_blank_lines: bool,
+) -> tuple[bool, str, str]:
+    if not comment_prefix:
+        return False, "", "comment_prefix must not be empty"
+    content, error = _read_utf8_for_edit(path)
+    if content is None:
+        return False, "", error
+    kept = []
+    for line in content.splitlines(keepends=True):
+        stripped = line.lstrip()
+        if stripped.startswith(comment_prefix):
+            continue
+        if not stripped and not keep_blank_lines:
+            continue
+        kept.append(line)
+    return True, "".join(kept), ""
+
+
+def _patch_coverage_python(path: str, patch: str) -> tuple[bool, float, str]:
+    ok, reason = _validate_patch_python(patch)
+    if not ok:
+        return False, 0.0, reason
+    try:
+        total = len(_read_lines_lossy(path))
+    except OSError as e:
+        return False, 0.0, str(e)
+    tagged = _classify_patch_lines(patch)
+    added = sum(1 for kind, _ in tagged if kind == "add")
+    removed = sum(1 for kind, _ in tagged if kind == "remove")
+    new_total = total - removed + added
+    if new_total <= 0:
+        return True, 1.0 if added or removed else 0.0, ""
+    return True, min(1.0, (added + removed) / new_total), ""
+
+
+_UNTIL_PATTERN_LINE_BYTES = 65536
+
+
+def _execute_until_pattern_python(
+    argv: list[str],
+    cwd: str,
+    timeout_seconds: float,
+    abort_pattern: str,
+) -> tuple[bool, int, str, str, str, bool, str]:
+    try:
+        pattern = re.compile(abort_pattern)
+    except re.error as e:
+        return False, -1, "", "", f"Invalid abort_pattern: {e}", False, ""
+    captured = {"stdout": bytearray(), "stderr": bytearray()}
+    pending = {"stdout": bytearray(), "stderr": bytearray()}
+    matched: list[str] = []
+    aborted = False
+
+    def check(raw: bytes) -> bool:
+        line = raw.decode("utf-8", errors="replace").rstrip("\r")
+        if pattern.search(line):
+            matched.append(line)
+            return True
+        return False
+
+    def on_chunk(stream: str, chunk: bytes) -> Optional[bool]:
+        nonlocal aborted
+        captured[stream].extend(chunk)
+        buffer = pending[stream]
+        buffer.extend(chunk)
+        while True:
+            newline = buffer.find(b"\n", 0, _UNTIL_PATTERN_LINE_BYTES + 1)
+            if newline != -1:
+                line = bytes(buffer[:newline])
+                del buffer[: newline + 1]
+            elif len(buffer) >= _UNTIL_PATTERN_LINE_BYTES:
+                line = bytes(buffer[:_UNTIL_PATTERN_LINE_BYTES])
+                del buffer[:_UNTIL_PATTERN_LINE_BYTES]
+            else:
+                return None
+            if check(line):
+                aborted = True
+                return True
+
+    returncode, timed_out, error = _stream_argv_python(argv, cwd, timeout_seconds, on_chunk)
+    if not matched:
+        for stream in ("stdout", "stderr"):
+            if pending[stream] and check(bytes(pending[stream])):
+                break
+    stdout = captured["stdout"].decode("utf-8", errors="replace")
+    stderr = captured["stderr"].decode("utf-8", errors="replace")
+    if aborted:
+        error = error or "Aborted: output matched abort_pattern"
+        return False, -1, stdout, stderr, error, True, matched[0]
+    success, code, error = _command_outcome(returncode, timed_out, error, timeout_seconds)
+    return success, code, stdout, stderr, error, False, matched[0] if matched else ""
+
+
+def _ranges_to_hunks_python(
+    old: str,
+    new: str,
+    ranges: list[tuple[int, int]],
+    context: int,
+) -> tuple[bool, str, str]:
+    if context < 0:
+        return False, "", "context must be non-negative"
+    if any(start < 1 or end < start for start, end in ranges):
+        return False, "", "ranges must be 1-based (start, end) with start <= end"
+    old_lines = old.splitlines(keepends=True)
+    new_lines = new.splitlines(keepends=True)
+    matcher = difflib.SequenceMatcher(None, old_lines, new_lines, autojunk=False)
+
+    def touches(i1: int, i2: int) -> bool:
+        # Old lines i1+1..i2 change; a pure insertion sits between lines i1 and i1+1.
+        first, last = (i1 + 1, i2) if i2 > i1 else (i1, i1 + 1)
+        return any(start <= last and first <= end for start, end in ranges)
+
+    def entry(token: str, line: str, old_pos: int, new_pos: int) -> _BodyLine:
+        marker = None if line.endswith("\n") else "\\ No newline at end of file"
+        return _BodyLine(token, line.rstrip("\n"), old_pos, new_pos, marker)
+
+    out: list[str] = []
+    shift = 0
+    for group in matcher.get_grouped_opcodes(context):
+        changes = [op for op in group if op[0] != "equal"]
+        if not any(touches(i1, i2) for _, i1, i2, _, _ in changes):
+            shift += sum((j2 - j1) - (i2 - i1) for _, i1, i2, j1, j2 in changes)
+            continue
+        body: list[_BodyLine] = []
+        for tag, i1, i2, j1, j2 in group:
+            if tag == "equal":
+                for k in range(i2 - i1):
+                    body.append(entry(" ", old_lines[i1 + k], i1 + k + 1, j1 + k + 1 - shift))
+                continue
+            for k in range(i2 - i1):
+                body.append(entry("-", old_lines[i1 + k], i1 + k + 1, j1 + 1 - shift))
+            for k in range(j2 - j1):
+                body.append(entry("+", new_lines[j1 + k], i2 + 1, j1 + k + 1 - shift))
+        out.extend(_emit_hunk(body, ""))
+    if not out:
+        return True, "", ""
+    return True, "\n".join(["--- a", "+++ b", *out]) + "\n", ""
+
+
+_DUPLICATE_LINES_MAX_LINES = 1_000_000
+
+
+def _find_duplicate_lines_python(
+    path: str,
+    min_run: int,
+) -> tuple[bool, list[tuple[int, int, int]], str]:
+    if min_run < 1:
+        return False, [], "min_run must be >= 1"
+    digests: list[int] = []
+    blank: list[bool] = []
+    try:
+        with open(path, "rb") as handle:
+            for raw in handle:
+                if len(digests) >= _DUPLICATE_LINES_MAX_LINES:
+                    return False, [], (
+                        f"File has more than {_DUPLICATE_LINES_MAX_LINES} lines; "
+                        "too large to scan for duplicates"
+                    )
+                line = raw.rstrip(b"\r\n")
+                digest = hashlib.blake2b(line, digest_size=8).digest()
+                digests.append(int.from_bytes(digest, "little"))
+                blank.append(not line.strip())
+    except OSError as e:
+        return False, [], str(e)
+
+    first_seen: dict[int, int] = {}
+    runs: list[tuple[int, int, int]] = []
+    i = 0
+    while i + min_run <= len(digests):
+        window = digests[i : i + min_run]
+        key = hash(tuple(window))
+        earlier = first_seen.get(key)
+        if (
+            earlier is None
+            or earlier + min_run > i
+            or all(blank[i : i + min_run])
+            or digests[earlier : earlier + min_run] != window
+        ):
+            first_seen.setdefault(key, i)
+            i += 1
+            continue
+        length = min_run
+        while i + length < len(digests) and earlier + length < i and (
+            digests[earlier + length] == digests[i + length]
+        ):
+            length += 1
+        runs.append((earlier + 1, i + 1, length))
+        i += length
+    return True, runs, ""
+
+
+def _patch_max_line_python(patch: str) -> tuple[bool, int, int, str]:
+    ok, reason = _validate_patch_python(patch)
+    if not ok:
+        return False, 0, 0, reason
+    max_old = max_new = 0
+    for kind, line in _classify_patch_lines(patch):
+        m = _HUNK_RE.match(line) if kind == "hunk" else None
+        if m:
+            old_start, old_count = int(m.group(1)), int(m.group(2) or "1")
+            new_start, new_count = int(m.group(3)), int(m.group(4) or "1")
+            max_old = max(max_old, old_start + max(old_count, 1) - 1)
+            max_new = max(max_new, new_start + max(new_count, 1) - 1)
+    return True, max_old, max_new, ""
+
+
+def _list_modified_since_python(root: str, since_unix: float) -> tuple[bool, list[str], str]:
+    if not os.path.isdir(root):
+        return False, [], f"Not a directory: {root}"
+    try:
+        files = _walk_files_python(root)
+        with ThreadPoolExecutor() as pool:
+            mtimes = list(pool.map(_mtime_or_zero, [path for _, path in files]))
+    except OSError as e:
+        return False, [], str(e)
+    return True, [rel for (rel, _), mtime in zip(files, mtimes) if mtime > since_unix], ""
+
+
+def _diff_trees_renames_python(
+    a_root: str,
+    b_root: str,
+    similarity: float,
+) -> tuple[bool, list[tuple[str, str]], list[str], list[str], list[str], str]:
+    if not 0.0 < similarity <= 1.0:
+        return False, [], [], [], [], "similarity must be in (0, 1]"
+    for root in (a_root, b_root):
+        if not os.path.isdir(root):
+            return False, [], [], [], [], f"Not a directory: {root}"
+    try:
+        with ThreadPoolExecutor(max_workers=2) as pool:
+            a_future = pool.submit(_tree_digests_python, a_root)
+            b_future = pool.submit(_tree_digests_python, b_root)
+            a_files, b_files = a_future.result(), b_future.result()
+    except OSError as e:
+        return False, [], [], [], [], str(e)
+
+    added = sorted(set(b_files) - set(a_files))
+    removed = sorted(set(a_files) - set(b_files))
+    modified = sorted(rel for rel in set(a_files) & set(b_files) if a_files[rel] != b_files[rel])
+
+    renames: list[tuple[str, str]] = []
+    by_digest: dict[str, list[str]] = {}
+    for rel in added:
+        by_digest.setdefault(b_files[rel], []).append(rel)
+    for rel in removed:
+        candidates = by_digest.get(a_files[rel])
+        if candidates:
+            renames.append((rel, candidates.pop(0)))
+
+    if similarity < 1.0:
+        paired_old = {old for old, _ in renames}
+        paired_new = {new for _, new in renames}
+        try:
+            old_text = {
+                rel: _read_lines_lossy(os.path.join(a_root, rel))
+                for rel in removed
+                if rel not in paired_old
+            }
+            new_text = {
+                rel: _read_lines_lossy(os.path.join(b_root, rel))
+                for rel in added
+                if rel not in paired_new
+            }
+        except OSError as e:
+            return False, [], [], [], [], str(e)
+        scored = []
+        for old_rel, old_lines in old_text.items():
+            for new_rel, new_lines in new_text.items():
+                matcher = difflib.SequenceMatcher(None, old_lines, new_lines, autojunk=False)
+                if matcher.real_quick_ratio() < similarity or matcher.quick_ratio() < similarity:
+                    continue
+                ratio = matcher.ratio()
+                if ratio >= similarity:
+                    scored.append((-ratio, old_rel, new_rel))
+        for _, old_rel, new_rel in sorted(scored):
+            if old_rel not in paired_old and new_rel not in paired_new:
+                renames.append((old_rel, new_rel))
+                paired_old.add(old_rel)
+                paired_new.add(new_rel)
+
+    renamed_old = {old for old, _ in renames}
+    renamed_new = {new for _, new in renames}
+    return (
+        True,
+        sorted(renames),
+        [rel for rel in added if rel not in renamed_new],
+        [rel for rel in removed if rel not in renamed_old],
+        modified,
+        "",
+    )
+
+
+def _fsync_directory(directory: str) -> None:
+    # Directories cannot be opened for fsync on Windows; the rename is as durable as it gets.
+    if os.name == "nt":
+        return
+    fd = os.open(directory, os.O_RDONLY)
+    try:
+        os.fsync(fd)
+    finally:
+        os.close(fd)
+
+
+def _write_text_file_atomic_python(
+    path: str,
+    content: str,
+    durable: bool,
+) -> tuple[bool, int, str]:
+    data = content.encode("utf-8")
+    try:
+        _atomic_write_bytes(path, data, durable)
+    except OSError as e:
+        return False, 0, str(e)
+    return True, len(data), ""
+
+
+def _read_bounded_python(path: str, max_lines: int, max_bytes: int) -> tuple[bool, str, bool, str]:
+    line_cap = max(int(max_lines), 0)
+    byte_cap = max(int(max_bytes), 0)
+    out = bytearray()
+    lines = 0
+    truncated = False
+    try:
+        with open(path, "rb") as handle:
+            while True:
+                raw = handle.readline(byte_cap - len(out) + 1)
+                if not raw:
+                    break
+                if lines >= line_cap:
+                    truncated = True
+                    break
+                if len(out) + len(raw) > byte_cap:
+                    out.extend(raw[: byte_cap - len(out)])
+                    truncated = True
+                    break
+                out.extend(raw)
+                if raw.endswith(b"\n"):
+                    lines += 1
+    except OSError as e:
+        return False, "", False, str(e)
+    decoder = codecs.getincrementaldecoder("utf-8")(errors="replace")
+    text = decoder.decode(bytes(out), final=not truncated)
+    return True, text, truncated, ""
+
+
+def _execute_command_hash_only_python(
+    argv: list[str],
+    cwd: str,
+    timeout_seconds: float,
+) -> tuple[bool, int, str, str]:
+    hashers = {"stdout": hashlib.sha256(), "stderr": hashlib.sha256()}
+
+    def on_chunk(stream: str, chunk: bytes) -> None:
+        hashers[stream].update(chunk)
+
+    returncode, timed_out, error = _stream_argv_python(argv, cwd, timeout_seconds, on_chunk)
+    success, code, error = _command_outcome(returncode, timed_out, error, timeout_seconds)
+    combined = hashlib.sha256(hashers["stdout"].digest() + hashers["stderr"].digest())
+    return success, code, combined.hexdigest(), error
+
+
+def _read_lines_capped_python(path: str, max_line_bytes: int) -> tuple[bool, list[str], bool, str]:
+    cap = max(int(max_line_bytes), 1)
+    lines: list[str] = []
+    truncated = False
+    try:
+        with open(path, "rb") as handle:
+            while True:
+                # Room for a CRLF terminator after a line that exactly fits.
+                raw = handle.readline(cap + 2)
+                if not raw:
+                    break
+                body = raw.rstrip(b"\r\n")
+                if len(body) <= cap:
+                    lines.append(body.decode("utf-8", errors="replace"))
+                    continue
+                truncated = True
+                decoder = codecs.getincrementaldecoder("utf-8")(errors="replace")
+                lines.append(decoder.decode(body[:cap]) + "…")
+                # Skip the remainder of the oversized line in bounded reads.
+                while not raw.endswith(b"\n"):
+                    raw = handle.readline(_READ_CHUNK_BYTES)
+                    if not raw:
+                        break
+    except OSError as e:
+        return False, [], False, str(e)
+    return True, lines, truncated, ""
+
+
+def _lock_file_handle(handle, exclusive: bool) -> None:
+    if os.name == "nt":
+        import msvcrt
+
+        handle.seek(0)
+        mode = msvcrt.LK_LOCK if exclusive else msvcrt.LK_UNLCK
+        msvcrt.locking(handle.fileno(), mode, 1)
+    else:
+        import fcntl
+
+        fcntl.flock(handle.fileno(), fcntl.LOCK_EX if exclusive else fcntl.LOCK_UN)
+
+
+def _increment_counter_file_python(path: str) -> tuple[bool, int, str]:
+    try:
+        with open(f"{path}.lock", "a+b") as lock:
+            _lock_file_handle(lock, True)
+            try:
+                try:
+                    with open(path, "rb") as handle:
+                        raw = handle.read().strip()
+                except FileNotFoundError:
+                    raw = b""
+                try:
+                    current = int(raw) if raw else 0
+                except ValueError:
+                    return False, 0, f"Counter file does not hold an integer: {path}"
+                if current < 0:
+                    return False, 0, f"Counter file holds a negative value: {path}"
+                value = current + 1
+                _atomic_write_bytes(path, f"{value}\n".encode("ascii"))
+            finally:
+                _lock_file_handle(lock, False)
+    except OSError as e:
+        return False, 0, str(e)
+    return True, value, ""
+
+
+def _check_blank_context_python(patch: str) -> tuple[bool, str]:
+    hunk_number = 0
+    context: list[str] = []
+
+    def all_blank() -> bool:
+        return bool(context) and all(not text.strip() for text in context)
+
+    for kind, line in _classify_patch_lines(patch):
+        if kind == "hunk":
+            if all_blank():
+                return False, f"Hunk {hunk_number} has only blank context lines"
+            hunk_number += 1
+            context = []
+        elif kind == "context":
+            context.append(line[1:])
+    if all_blank():
+        return False, f"Hunk {hunk_number} has only blank context lines"
+    return True, "ok"
+
+
+def _hash_and_size_python(path: str) -> tuple[bool, str, int, str]:
+    digest = hashlib.sha256()
+    size = 0
+    try:
+        with open(path, "rb") as handle:
+            for chunk in iter(lambda: handle.read(_READ_CHUNK_BYTES), b""):
+                digest.update(chunk)
+                size += len(chunk)
+    except OSError as e:
+        return False, "", 0, str(e)
+    return True, digest.hexdigest(), size, ""
+
+
+def _execute_command_timestamped_python(
+    argv: list[str],
+    cwd: str,
+    timeout_seconds: float,
+) -> tuple[bool, int, list[tuple[float, str, str]], str]:
+    decoder_factory = codecs.getincrementaldecoder("utf-8")
+    decoders = {stream: decoder_factory(errors="replace") for stream in ("stdout", "stderr")}
+    entries: list[tuple[float, str, str]] = []
+    started = time.monotonic()
+
+    def on_chunk(stream: str, chunk: bytes) -> None:
+        text = decoders[stream].decode(chunk)
+        if text:
+            entries.append((time.monotonic() - started, stream, text))
+
+    returncode, timed_out, error = _stream_argv_python(argv, cwd, timeout_seconds, on_chunk)
+    for stream in ("stdout", "stderr"):
+        tail = decoders[stream].decode(b"", final=True)
+        if tail:
+            entries.append((time.monotonic() - started, stream, tail))
+    success, code, error = _command_outcome(returncode, timed_out, error, timeout_seconds)
+    return success, code, entries, error
+
+
+def _collapse_repeats_python(text: str) -> str:
+    out: list[str] = []
+    run_line = ""
+    run_length = 0
+
+    def flush() -> None:
+        if run_length == 1:
+            out.append(run_line)
+        elif run_length > 1:
+            body = run_line.rstrip("\r\n")
+            ending = run_line[len(body) :]
+            out.append(f"{body} (repeated {run_length} times){ending}")
+
+    for line in text.splitlines(keepends=True):
+        if run_length and line.rstrip("\r\n") == run_line.rstrip("\r\n"):
+            run_length += 1
+            run_line = line
+            continue
+        flush()
+        run_line, run_length = line, 1
+    flush()
+    return "".join(out)
+
+
+def _read_dual_python(path: str) -> tuple[bool, bytes, str, bool, str]:
+    try:
+        with open(path, "rb") as handle:
+            data = handle.read()
+    except OSError as e:
+        return False, b"", "", False, str(e)
+    try:
+        return True, data, data.decode("utf-8"), True, ""
+    except UnicodeDecodeError:
+        return True, data, data.decode("utf-8", errors="replace"), False, ""
+
+
+_PROGRESS_INTERVAL_SECONDS = 0.1
+
+
+def _hash_tree_progress_python(
+    root: str,
+    callback: Callable[[int, int], object],
+    respect_gitignore: bool,
+) -> tuple[bool, str, str]:
+    if not os.path.isdir(root):
+        return False, "", f"Not a directory: {root}"
+    combined = hashlib.sha256()
+    combined.update(b"ignore:\n")
+    try:
+        files = _walk_files_python(root, respect_gitignore)
+        total = len(files)
+        last_report = time.monotonic()
+        with ThreadPoolExecutor() as pool:
+            digests = pool.map(_sha256_file, [path for _, path in files])
+            for done, ((rel, _), file_digest) in enumerate(zip(files, digests), start=1):
+                combined.update(f"{rel}\0{file_digest}\n".encode("utf-8"))
+                now = time.monotonic()
+                if done < total and now - last_report < _PROGRESS_INTERVAL_SECONDS:
+                    continue
+                last_report = now
+                try:
+                    callback(done, total)
+                except Exception as e:
+                    return False, "", f"Progress callback failed: {e}"
+    except OSError as e:
+        return False, "", str(e)
+    if total == 0:
+        try:
+            callback(0, 0)
+        except Exception as e:
+            return False, "", f"Progress callback failed: {e}"
+    return True, combined.hexdigest(), ""
+
+
+def _compile_regex_python(pattern: str) -> tuple[bool, int, str]:
+    try:
+        return True, re.compile(pattern).groups, ""
+    except re.error as e:
+        return False, 0, f"Invalid regex: {e}"
+
+
+def _byte_histogram_python(path: str, sample_bytes: int) -> tuple[bool, list[int], str]:
+    counts = [0] * 256
+    remaining = max(int(sample_bytes), 0)
+    try:
+        with open(path, "rb") as handle:
+            while remaining > 0:
+                chunk = handle.read(min(remaining, _READ_CHUNK_BYTES))
+                if not chunk:
+                    break
+                remaining -= len(chunk)
+                for value in range(256):
+                    counts[value] += chunk.count(value)
+    except OSError as e:
+        return False, [0] * 256, str(e)
+    return True, counts, ""
+
+
+def _diff_to_patch_file_python(
+    old_path: str,
+    new_path: str,
+    out_path: str,
+    context: int,
+) -> tuple[bool, int, str]:
+    if context < 0:
+        return False, 0, "context must be non-negative"
+    old, error = _read_utf8_for_edit(old_path)
+    if old is None:
+        return False, 0, error
+    new, error = _read_utf8_for_edit(new_path)
+    if new is None:
+        return False, 0, error
+    patch = _unified_diff_text(old, new, f"a/{old_path}", f"b/{new_path}", context)
+    data = patch.encode("utf-8")
+    try:
+        _atomic_write_bytes(out_path, data)
+    except OSError as e:
+        return False, 0, str(e)
+    return True, len(data), ""
+
+
+def _hash_with_python(data: bytes, algorithm: str) -> tuple[bool, str, str]:
+    name = str(algorithm).strip().lower()
+    if name in ("sha256", "sha512", "md5"):
+        return True, hashlib.new(name, data).hexdigest(), ""
+    if name == "blake3":
+        try:
+            import blake3  # type: ignore
+        except ImportError:
+            return False, "", "blake3 is unavailable without the Rust core or blake3 package"
+        return True, blake3.blake3(data).hexdigest(), ""
+    return False, "", f"Unknown hash algorithm: {algorithm}"
+
+
+def _grep_context_numbered_python(
+    path: str,
+    pattern: str,
+    before: int,
+    after: int,
+) -> tuple[bool, str, str]:
+    try:
+        regex = re.compile(pattern)
+    except re.error as e:
+        return False, "", f"Invalid regex: {e}"
+    try:
+        lines = _read_lines_lossy(path)
+    except OSError as e:
+        return False, "", str(e)
+
+    matches = [index for index, line in enumerate(lines) if regex.search(line)]
+    regions: list[list[int]] = []
+    for index in matches:
+        start, end = max(index - max(before, 0), 0), min(index + max(after, 0), len(lines) - 1)
+        if regions and start <= regions[-1][1] + 1:
+            regions[-1][1] = max(regions[-1][1], end)
+        else:
+            regions.append([start, end])
+
+    matched = set(matches)
+    out: list[str] = []
+    for start, end in regions:
+        if out:
+            out.append("--")
+        for index in range(start, end + 1):
+            sep = ":" if index in matched else "-"
+            out.append(f"{index + 1}{sep}{lines[index]}")
+    return True, "\n".join(out) + ("\n" if out else ""), ""
+
+
+def _patch_header_path(line: str) -> Optional[str]:
+    """Return the normalized path named by a ---/+++ header, or None for /dev/null."""
+    raw = line[4:].split("\t", 1)[0].strip()
+    if raw == "/dev/null":
+        return None
+    if raw.startswith(("a/", "b/")):
+        raw = raw[2:]
+    return posixpath.normpath(raw.replace("\\", "/"))
+
+
+def _path_under_prefix(path: str, prefix: str) -> bool:
+    base = posixpath.normpath(prefix.replace("\\", "/"))
+    if base == ".":
+        return not (path == ".." or path.startswith("../") or path.startswith("/"))
+    return path == base or path.startswith(base.rstrip("/") + "/")
+
+
+def _patch_within_paths_python(
+    patch: str,
+    allowed_prefixes: list[str],
+) -> tuple[bool, list[str], str]:
+    headers = [
+        line
+        for kind, line in _classify_patch_lines(patch)
+        if kind in ("old_header", "new_header")
+    ]
+    if not headers:
+        return False, [], "Patch has no file headers"
+    touched = {path for path in map(_patch_header_path, headers) if path is not None}
+    violating = sorted(
+        path
+        for path in touched
+        if not any(_path_under_prefix(path, prefix) for prefix in allowed_prefixes)
+    )
+    return not violating, violating, ""
+
+
+class _TransformAborted(Exception):
+    pass
+
+
+def _stream_transform_python(
+    src: str,
+    dst: str,
+    callback: Callable[[str], Optional[str]],
+) -> tuple[bool, int, str]:
+    directory = os.path.dirname(os.path.abspath(dst))
+    written = 0
+    try:
+        fd, tmp_path = tempfile.mkstemp(
+            dir=directory, prefix=f".{os.path.basename(dst)}.", suffix=".tmp"
+        )
+    except OSError as e:
+        return False, 0, str(e)
+    try:
+        with open(src, "rb") as reader, os.fdopen(fd, "wb") as writer:
+            for raw in reader:
+                body = raw.rstrip(b"\r\n")
+                ending = raw[len(body) :]
+                try:
+                    updated = callback(body.decode("utf-8", errors="replace"))
+                except Exception as e:
+                    raise _TransformAborted(f"Transform callback failed: {e}") from e
+                if updated is None:
+                    continue
+                data = str(updated).encode("utf-8") + ending
+                writer.write(data)
+                written += len(data)
+        os.replace(tmp_path, dst)
+    except (OSError, _TransformAborted) as e:
+        try:
+            os.unlink(tmp_path)
+        except OSError:
+            pass
+        return False, 0, str(e)
+    return True, written, ""
+
+
+def _timed_read_python(path: str) -> tuple[bool, str, float, str]:
+    started = time.perf_counter()
+    content, error = _read_utf8_for_edit(path)
+    elapsed = time.perf_counter() - started
+    if content is None:
+        return False, "", elapsed, error
+    return True, content, elapsed, ""
+
+
+def _timed_write_python(path: str, content: str) -> tuple[bool, int, float, str]:
+    data = content.encode("utf-8")
+    started = time.perf_counter()
+    try:
+        with open(path, "wb") as handle:
+            handle.write(data)
+    except OSError as e:
+        return False, 0, time.perf_counter() - started, str(e)
+    return True, len(data), time.perf_counter() - started, ""
+
+
+def _split_file_at_line_python(
+    path: str,
+    line: int,
+    first_out: str,
+    second_out: str,
+) -> tuple[bool, str]:
+    if line < 0:
+        return False, "line must be >= 0"
+    try:
+        with open(path, "rb") as handle:
+            lines = handle.read().splitlines(keepends=True)
+        _atomic_write_bytes(first_out, b"".join(lines[:line]))
+        _atomic_write_bytes(second_out, b"".join(lines[line:]))
+    except OSError as e:
+        return False, str(e)
+    return True, ""
+
+
+def _concat_files_python(
+    paths: list[str],
+    out_path: str,
+    separator: str,
+    skip_missing: bool,
+) -> tuple[bool, int, str]:
+    sep = separator.encode("utf-8")
+    directory = os.path.dirname(os.path.abspath(out_path))
+    written = 0
+    try:
+        fd, tmp_path = tempfile.mkstemp(
+            dir=directory, prefix=f".{os.path.basename(out_path)}.", suffix=".tmp"
+        )
+    except OSError as e:
+        return False, 0, str(e)
+    try:
+        with os.fdopen(fd, "wb") as writer:
+            first = True
+            for path in paths:
+                try:
+                    reader = open(path, "rb")
+                except FileNotFoundError:
+                    if skip_missing:
+                        continue
+                    raise
+                with reader:
+                    if not first:
+                        writer.write(sep)
+                        written += len(sep)
+                    first = False
+                    for chunk in iter(lambda: reader.read(_READ_CHUNK_BYTES), b""):
+                        writer.write(chunk)
+                        written += len(chunk)
+        os.replace(tmp_path, out_path)
+    except OSError as e:
+        try:
+            os.unlink(tmp_path)
+        except OSError:
+            pass
+        return False, 0, str(e)
+    return True, written, ""
+
+
+_WHATWG_ENCODING_NAMES = {
+    "cp1252": "windows-1252",
+    "cp1251": "windows-1251",
+    "cp932": "Shift_JIS",
+    "shift_jis": "Shift_JIS",
+    "euc_jp": "EUC-JP",
+    "gbk": "GBK",
+    "gb2312": "GBK",
+    "gb18030": "gb18030",
+    "big5": "Big5",
+    "cp949": "EUC-KR",
+    "euc_kr": "EUC-KR",
+    "iso8859-1": "windows-1252",
+    "latin-1": "windows-1252",
+    "koi8-r": "KOI8-R",
+}
+
+
+def _guess_encoding(data: bytes) -> str:
+    if data.startswith(codecs.BOM_UTF8):
+        return "UTF-8"
+    if data.startswith(codecs.BOM_UTF16_LE):
+        return "UTF-16LE"
+    if data.startswith(codecs.BOM_UTF16_BE):
+        return "UTF-16BE"
+    if len(data) >= 4:
+        # Mostly-ASCII UTF-16 has a NUL in every other byte.
+        even_nuls = data[0::2].count(0) / len(data[0::2])
+        odd_nuls = data[1::2].count(0) / len(data[1::2])
+        if odd_nuls > 0.4 and even_nuls < 0.1:
+            return "UTF-16LE"
+        if even_nuls > 0.4 and odd_nuls < 0.1:
+            return "UTF-16BE"
+    try:
+        data.decode("utf-8")
+        return "UTF-8"
+    except UnicodeDecodeError:
+        pass
+    preferred = locale.getpreferredencoding(False)
+    try:
+        name = codecs.lookup(preferred).name
+        if name not in ("utf-8", "ascii"):
+            data.decode(name)
+            return _WHATWG_ENCODING_NAMES.get(name, preferred)
+    except (LookupError, UnicodeDecodeError):
+        pass
+    return "windows-1252"
+
+
+def _detect_output_encoding_python(
+    argv: list[str],
+    cwd: str,
+    timeout_seconds: float,
+) -> tuple[bool, str, str]:
+    captured = bytearray()
+
+    def on_chunk(stream: str, chunk: bytes) -> None:
+        captured.extend(chunk)
+
+    _, timed_out, error = _stream_argv_python(argv, cwd, timeout_seconds, on_chunk)
+    if error:
+        return False, "", error
+    if timed_out:
+        return False, "", f"Command timed out after {timeout_seconds}s"
+    return True, _guess_encoding(bytes(captured)), ""
+
+
+def _read_byte_range_python(path: str, start: int, end: int) -> tuple[bool, str, str]:
+    if start < 0 or start > end:
+        return False, "", f"Invalid byte range [{start}, {end})"
+    try:
+        with open(path, "rb") as handle:
+            handle.seek(start)
+            data = handle.read(end - start)
+    except OSError as e:
+        return False, "", str(e)
+    lead = 0
+    while lead < min(3, len(data)) and (data[lead] & 0xC0) == 0x80:
+        lead += 1
+    decoder = codecs.getincrementaldecoder("utf-8")(errors="replace")
+    return True, decoder.decode(data[lead:]), ""
+
+
+def _execute_with_retries_python(
+    argv: list[str],
+    cwd: str,
+    timeout_seconds: float,
+    max_attempts: int,
+    backoff_seconds: float,
+    jitter_fraction: float,
+    seed: Optional[int],
+) -> tuple[bool, int, str, str, str, list[tuple[float, int]]]:
+    if max_attempts < 1:
+        return False, -1, "", "", "max_attempts must be >= 1", []
+    jitter = min(max(float(jitter_fraction), 0.0), 1.0)
+    rng = random.Random(seed)
+    attempts: list[tuple[float, int]] = []
+    for attempt in range(1, max_attempts + 1):
+        started = time.monotonic()
+        success, code, stdout, stderr, error = _capture_argv_python(argv, cwd, timeout_seconds)
+        attempts.append((time.monotonic() - started, code))
+        if success or attempt == max_attempts:
+            break
+        delay = max(float(backoff_seconds), 0.0) * 2 ** (attempt - 1)
+        time.sleep(delay * (1.0 + rng.uniform(-jitter, jitter)))
+    return success, code, stdout, stderr, error, attempts
+
+
+def _patch_roundtrips_python(original: str, patch: str) -> tuple[bool, bool, str]:
+    patched, error = _apply_patch_python(original, patch)
+    if patched is None:
+        return False, False, f"Forward apply failed: {error}"
+    ok, reversed_patch, error = _reverse_patch_python(patch)
+    if not ok:
+        return False, False, f"Reverse failed: {error}"
+    restored, error = _apply_patch_python(patched, reversed_patch)
+    if restored is None:
+        return True, False, f"Reverse apply failed: {error}"
+    return True, restored == original, ""
+
+
+def _list_by_mtime_python(
+    root: str,
+    limit: int,
+    respect_gitignore: bool,
+) -> tuple[bool, list[tuple[str, float]], str]:
+    if not os.path.isdir(root):
+        return False, [], f"Not a directory: {root}"
+    if limit <= 0:
+        return True, [], ""
+    heap: list[tuple[float, str]] = []
+    try:
+        for rel, path, is_dir in _iter_entries_python(root, respect_gitignore):
+            if is_dir:
+                continue
+            try:
+                item = (os.stat(path).st_mtime, rel)
+            except FileNotFoundError:
+                continue
+            if len(heap) < limit:
+                heapq.heappush(heap, item)
+            elif item > heap[0]:
+                heapq.heapreplace(heap, item)
+    except OSError as e:
+        return False, [], str(e)
+    newest = sorted(heap, key=lambda item: (-item[0], item[1]))
+    return True, [(rel, mtime) for mtime, rel in newest], ""
+
+
+_LINE_ENDINGS = {"lf": "\n", "crlf": "\r\n", "cr": "\r"}
+
+
+def _convert_line_endings(content: str, line_ending: str) -> Optional[str]:
+    mode = str(line_ending).strip().lower()
+    if mode == "keep":
+        return content
+    target = _LINE_ENDINGS.get(mode)
+    if target is None:
+        return None
+    normalized = content.replace("\r\n", "\n").replace("\r", "\n")
+    return normalized if target == "\n" else normalized.replace("\n", target)
+
+
+def _write_text_file_python(path: str, content: str) -> tuple[bool, int, str]:
+    data = content.encode("utf-8")
+    try:
+        with open(path, "wb") as handle:
+            handle.write(data)
+    except OSError as e:
+        return False, 0, str(e)
+    return True, len(data), ""
+
+
+def _repo_relative_path_python(path: str) -> tuple[bool, str, str, str]:
+    target = os.path.realpath(path)
+    current = target if os.path.isdir(target) else os.path.dirname(target)
+    while True:
+        if os.path.exists(os.path.join(current, ".git")):
+            rel = os.path.relpath(target, current).replace(os.sep, "/")
+            return True, current, rel, ""
+        parent = os.path.dirname(current)
+        if parent == current:
+            return False, "", "", f"Not inside a git repository: {path}"
+        current = parent
+
+
+def _reject_json_constant(name: str) -> None:
+    raise ValueError(f"{name} is not valid JSON")
+
+
+def _execute_json_python(
+    argv: list[str],
+    cwd: str,
+    timeout_seconds: float,
+) -> tuple[bool, int, str, bool, str]:
+    success, code, stdout, _, error = _capture_argv_python(argv, cwd, timeout_seconds)
+    try:
+        json.loads(stdout, parse_constant=_reject_json_constant)
+        is_json = True
+    except ValueError:
+        is_json = False
+    return success, code, stdout, is_json, error
+
+
+_GIT_DIFF_RE = re.compile(r"^diff --git a/(.+) b/(.+)$")
+
+
+class _PatchFileOp:
+    def __init__(self) -> None:
+        self.old: Optional[str] = None
+        self.new: Optional[str] = None
+        self.created = False
+        self.deleted = False
+        self.has_headers = False
+        self.changes: list[str] = []
+
+    def key_lines(self) -> list[str]:
+        if self.created:
+            lines = [f"create {self.new or self.old}"]
+        elif self.deleted:
+            lines = [f"delete {self.old or self.new}"]
+        elif self.old != self.new:
+            lines = [f"rename {self.old} -> {self.new}"]
+        else:
+            lines = [f"modify {self.old}"]
+        return lines + self.changes
+
+
+def _rename_aware_fingerprint_python(patch: str) -> tuple[bool, str, str]:
+    files: list[_PatchFileOp] = []
+    current: Optional[_PatchFileOp] = None
+    old_line = 0
+    in_run = False
+    for kind, line in _classify_patch_lines(patch):
+        if kind == "meta":
+            git = _GIT_DIFF_RE.match(line)
+            if git:
+                current = _PatchFileOp()
+                current.old = posixpath.normpath(git.group(1))
+                current.new = posixpath.normpath(git.group(2))
+                files.append(current)
+            elif current is None:
+                continue
+            elif line.startswith("rename from "):
+                current.old = posixpath.normpath(line[len("rename from "):].strip())
+            elif line.startswith("rename to "):
+                current.new = posixpath.normpath(line[len("rename to "):].strip())
+            elif line.startswith("new file mode"):
+                current.created = True
+            elif line.startswith("deleted file mode"):
+                current.deleted = True
+        elif kind == "old_header":
+            if current is None or current.has_headers or current.changes:
+                current = _PatchFileOp()
+                files.append(current)
+            current.has_headers = True
+            path = _patch_header_path(line)
+            current.created = current.created or path is None
+            if path is not None:
+                current.old = path
+        elif kind == "new_header" and current is not None:
+            path = _patch_header_path(line)
+            current.deleted = current.deleted or path is None
+            if path is not None:
+                current.new = path
+                current.old = current.old or path
+            elif current.old is not None:
+                current.new = current.old
+        elif current is None:
+            continue
+        elif kind == "hunk":
+            m = _HUNK_RE.match(line)
+            old_line = int(m.group(1)) if m else 0
+            in_run = False
+        elif kind == "context":
+            old_line += 1
+            in_run = False
+        elif kind in ("remove", "add", "marker"):
+            if not in_run:
+                current.changes.append(f"@ {old_line}")
+                in_run = True
+            current.changes.append(line)
+            if kind == "remove":
+                old_line += 1
+    if not files:
+        return False, "", "Patch has no file operations"
+    for op in files:
+        if op.created and op.old is None:
+            op.old = op.new
+    files.sort(key=lambda op: (op.old or "", op.new or ""))
+    digest = hashlib.sha256()
+    for op in files:
+        for key_line in op.key_lines():
+            digest.update(key_line.encode("utf-8", errors="surrogateescape") + b"\n")
+        digest.update(b"\0")
+    return True, digest.hexdigest(), ""
+
+
+def _compile_glob_set(patterns: list[str]) -> re.Pattern[str]:
+    """Combine globs into one alternation so each path is tested with a single match."""
+    bodies = [_glob_to_regex(pattern.strip().lstrip("/")) for pattern in patterns]
+    return re.compile("|".join(f"(?:{body})" for body in bodies))
+
+
+def _glob_match_many_python(
+    root: str,
+    includes: list[str],
+    excludes: list[str],
+    respect_gitignore: bool,
+) -> tuple[bool, list[str], str]:
+    if not os.path.isdir(root):
+        return False, [], f"Not a directory: {root}"
+    if not includes:
+        return False, [], "No include patterns given"
+    if any(not pattern.strip() for pattern in [*includes, *excludes]):
+        return False, [], "Glob pattern is empty"
+    try:
+        include_set = _compile_glob_set(includes)
+        exclude_set = _compile_glob_set(excludes) if excludes else None
+    except re.error as e:
+        return False, [], f"Invalid glob pattern: {e}"
+    try:
+        files = _walk_files_python(root, respect_gitignore)
+    except OSError as e:
+        return False, [], str(e)
+    matched = [
+        rel
+        for rel, _ in files
+        if include_set.fullmatch(rel) and not (exclude_set and exclude_set.fullmatch(rel))
+    ]
+    return True, matched, ""
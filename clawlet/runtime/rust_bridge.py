@@ -3,8 +3,115 @@
 from __future__ import annotations
 
 import hashlib
-import re
-from typing import Optional, Tuple
+import os
+import threading
+from typing import Callable, Optional, Tuple, Union
+
+from clawlet.runtime.bridge_fallbacks import (
+    _apply_patch_python,
+    _apply_string_edit_python,
+    _apply_string_edits_python,
+    _build_manifest_python,
+    _byte_histogram_python,
+    _capture_argv_python,
+    _check_argv_paths_python,
+    _check_blank_context_python,
+    _chunk_text_python,
+    _collapse_repeats_python,
+    _compile_regex_python,
+    _concat_files_python,
+    _convert_line_endings,
+    _count_glob_python,
+    _count_occurrences_in_file_python,
+    _debug_open_handles_python,
+    _detect_moves_python,
+    _detect_output_encoding_python,
+    _diff_ops_python,
+    _diff_to_patch_file_python,
+    _diff_trees_python,
+    _diff_trees_renames_python,
+    _ends_with_newline_python,
+    _execute_command_argv_bounded_python,
+    _execute_command_hash_only_python,
+    _execute_command_min_runtime_python,
+    _execute_command_status_python,
+    _execute_command_streaming_python,
+    _execute_command_timestamped_python,
+    _execute_command_to_queue_python,
+    _execute_hermetic_python,
+    _execute_json_python,
+    _execute_parallel_python,
+    _execute_until_pattern_python,
+    _execute_with_retries_python,
+    _find_duplicate_lines_python,
+    _find_symlinks_python,
+    _format_patch_python,
+    _get_permissions_python,
+    _git_blob_hash_python,
+    _glob_match_many_python,
+    _grep_context_numbered_python,
+    _hash_and_size_python,
+    _hash_parts_python,
+    _hash_tree_progress_python,
+    _hash_tree_python,
+    _hash_with_previous_python,
+    _hash_with_python,
+    _hunks_status_python,
+    _increment_counter_file_python,
+    _is_patch_reversible_python,
+    _latest_mtime_python,
+    _line_hashes_python,
+    _list_by_mtime_python,
+    _list_dir_detailed_python,
+    _list_modified_since_python,
+    _locate_hunk_python,
+    _merge3_python,
+    _minimize_patch_python,
+    _normalize_extensions,
+    _offset_to_position_python,
+    _patch_coverage_python,
+    _patch_max_line_python,
+    _patch_roundtrips_python,
+    _patch_within_paths_python,
+    _position_to_offset_python,
+    _preview_patch_on_file_python,
+    _ranges_to_hunks_python,
+    _read_base64_python,
+    _read_bounded_python,
+    _read_byte_range_python,
+    _read_dual_python,
+    _read_expanded_python,
+    _read_line_ranges_python,
+    _read_lines_capped_python,
+    _read_snapshot_python,
+    _read_text_bom_python,
+    _read_text_retry_python,
+    _read_text_strict_python,
+    _read_with_language_python,
+    _read_without_comments_python,
+    _remap_line_python,
+    _rename_aware_fingerprint_python,
+    _repo_relative_path_python,
+    _reverse_patch_python,
+    _secure_delete_python,
+    _set_permissions_python,
+    _sha256_file,
+    _spawn_detached_python,
+    _split_file_at_line_python,
+    _stream_transform_python,
+    _string_edits_to_patch_python,
+    _timed_read_python,
+    _timed_write_python,
+    _tokenize_words_python,
+    _transform_file_python,
+    _truncate_to_last_bytes_python,
+    _validate_patch_against_file_python,
+    _validate_patch_python,
+    _verify_manifest_python,
+    _write_base64_python,
+    _write_text_file_atomic_python,
+    _write_text_file_python,
+)
 
 
 def is_available() -> bool:
@@ -25,17 +132,29 @@ def fast_hash(payload: str) -> str:
         return hashlib.sha256(payload.encode("utf-8")).hexdigest()
 
 
-def validate_patch(patch: str) -> Tuple[bool, str]:
-    """Validate patch format with Rust extension fallback."""
+def validate_patch(patch: str, reject_blank_context: bool = False) -> Tuple[bool, str]:
+    """
+    Validate patch format with Rust extension fallback.
+
+    With `reject_blank_context`, a hunk whose context lines are all blank is reported
+    as likely corruption even if its counts add up. Off by default because genuine
+    whitespace-only edits can look the same.
+    """
     try:
         import clawlet_rust_core  # type: ignore
 
-        result = clawlet_rust_core.validate_patch(patch)
+        if reject_blank_context:
+            result = clawlet_rust_core.validate_patch(patch, True)
+        else:
+            result = clawlet_rust_core.validate_patch(patch)
         if isinstance(result, tuple) and len(result) == 2:
             return bool(result[0]), str(result[1])
         return True, "ok"
     except Exception:
-        return _validate_patch_python(patch)
+        ok, reason = _validate_patch_python(patch)
+        if ok and reject_blank_context:
+            return _check_blank_context_python(patch)
+        return ok, reason
 
 
 def apply_unified_patch(original_text: str, patch: str) -> Optional[tuple[bool, str, str]]:
@@ -55,29 +174,41 @@ def execute_command_argv(
     argv: list[str],
     cwd: str,
     timeout_seconds: float,
+    collapse_repeated_lines: bool = False,
 ) -> Optional[tuple[bool, int, str, str, str]]:
     """
     Execute command argv via Rust core when available.
 
+    With `collapse_repeated_lines`, runs of identical lines in stdout and stderr are
+    folded as by collapse_repeats. Without Rust the command then runs through a Python
+    fallback so the option is still honoured.
+
     Returns:
-      - tuple(success, returncode, stdout, stderr, error) if Rust path succeeded
-      - None when Rust core is unavailable (caller should use Python fallback path)
+      - tuple(success, returncode, stdout, stderr, error) if the command ran
+      - None when Rust core is unavailable and `collapse_repeated_lines` is off (caller
+        should use Python fallback path)
     """
     try:
         import clawlet_rust_core  # type: ignore
 
         result = clawlet_rust_core.execute_command_argv(argv, cwd, float(timeout_seconds))
         if isinstance(result, tuple) and len(result) == 5:
+            stdout, stderr = str(result[2]), str(result[3])
+            if collapse_repeated_lines:
+                stdout, stderr = collapse_repeats(stdout), collapse_repeats(stderr)
             return (
                 bool(result[0]),
                 int(result[1]),
-                str(result[2]),
-                str(result[3]),
+                stdout,
+                stderr,
                 str(result[4]),
             )
     except Exception:
+        pass
+    if not collapse_repeated_lines:
         return None
-    return None
+    success, code, stdout, stderr, error = _capture_argv_python(argv, cwd, timeout_seconds)
+    return success, code, collapse_repeats(stdout), collapse_repeats(stderr), error
 
 
 def read_text_file(path: str) -> Optional[tuple[bool, str, str]]:
@@ -93,17 +224,37 @@ def read_text_file(path: str) -> Optional[tuple[bool, str, str]]:
     return None
 
 
-def write_text_file(path: str, content: str) -> Optional[tuple[bool, int, str]]:
-    """Write UTF-8 text file via Rust core when available."""
+def write_text_file(
+    path: str,
+    content: str,
+    line_ending: str = "keep",
+) -> Optional[tuple[bool, int, str]]:
+    """
+    Write UTF-8 text file via Rust core when available.
+
+    `line_ending` is "lf", "crlf" or "cr" to convert every line break before writing,
+    or "keep" to write the content as given. An unknown `line_ending` is rejected and
+    a conversion is always honoured, falling back to a Python write without Rust.
+
+    Returns:
+      - tuple(success, bytes_written, error) if the content was written or rejected
+      - None when Rust core is unavailable and nothing needed converting (caller should
+        use Python fallback path)
+    """
+    converted = _convert_line_endings(str(content), line_ending)
+    if converted is None:
+        return False, 0, f"Unknown line_ending: {line_ending}"
     try:
         import clawlet_rust_core  # type: ignore
 
-        result = clawlet_rust_core.write_text_file(str(path), str(content))
+        result = clawlet_rust_core.write_text_file(str(path), converted)
         if isinstance(result, tuple) and len(result) == 3:
             return (bool(result[0]), int(result[1]), str(result[2]))
     except Exception:
+        pass
+    if converted == content:
         return None
-    return None
+    return _write_text_file_python(path, converted)
 
 
 def list_dir_entries(path: str) -> Optional[tuple[bool, list[tuple[str, bool]], str]]:
@@ -125,60 +276,2202 @@ def list_dir_entries(path: str) -> Optional[tuple[bool, list[tuple[str, bool]],
     return None
 
 
-_HUNK_RE = re.compile(r"^@@ -(\d+)(?:,(\d+))? \+(\d+)(?:,(\d+))? @@")
-
-
-def _validate_patch_python(patch: str) -> Tuple[bool, str]:
-    if not patch or not patch.strip():
-        return False, "Patch is empty"
-
-    lines = patch.splitlines()
-    saw_hunk = False
-    in_hunk = False
-    expected_old = 0
-    expected_new = 0
-    seen_old = 0
-    seen_new = 0
-
-    for line in lines:
-        m = _HUNK_RE.match(line)
-        if m:
-            if in_hunk:
-                if expected_old > 0 and seen_old != expected_old:
-                    return False, f"Old-side hunk line count mismatch: expected {expected_old}, got {seen_old}"
-                if expected_new > 0 and seen_new != expected_new:
-                    return False, f"New-side hunk line count mismatch: expected {expected_new}, got {seen_new}"
-            saw_hunk = True
-            in_hunk = True
-            expected_old = int(m.group(2) or "1")
-            expected_new = int(m.group(4) or "1")
-            seen_old = 0
-            seen_new = 0
-            continue
-
-        if not in_hunk:
-            continue
-
-        if line.startswith(" "):
-            seen_old += 1
-            seen_new += 1
-        elif line.startswith("-"):
-            seen_old += 1
-        elif line.startswith("+"):
-            seen_new += 1
-        elif line.startswith("\\ No newline"):
-            pass
-        elif line.startswith("--- ") or line.startswith("+++ "):
-            pass
-        else:
-            return False, f"Unsupported patch line in hunk: {line}"
+def locate_hunk(
+    original: str,
+    hunk_context: list[str],
+    stated_line: int = 1,
+    window: int = 50,
+    max_fuzz: int = 2,
+) -> tuple[bool, Optional[int], int, str]:
+    """
+    Find where a hunk's old-side lines actually fit in a drifted file.
+
+    Searches `window` lines either side of the 1-based `stated_line` and scores each
+    candidate by the number of mismatching lines (the fuzz cost). Ties go to the
+    candidate closest to the stated position.
+
+    Returns:
+      - tuple(ok, offset, cost, error) where offset is the 0-based line index of the
+        best match, or None when no candidate is within `max_fuzz`
+    """
+    try:
+        import clawlet_rust_core  # type: ignore
+
+        result = clawlet_rust_core.locate_hunk(
+            str(original),
+            [str(line) for line in hunk_context],
+            int(stated_line),
+            int(window),
+            int(max_fuzz),
+        )
+        if isinstance(result, tuple) and len(result) == 4:
+            offset = None if result[1] is None else int(result[1])
+            return (bool(result[0]), offset, int(result[2]), str(result[3]))
+    except Exception:
+        pass
+    return _locate_hunk_python(original, hunk_context, stated_line, window, max_fuzz)
+
+
+def get_permissions(path: str) -> tuple[bool, int, bool, str]:
+    """
+    Read a file's permission bits via Rust core when available.
+
+    On Unix the real mode bits are returned. On Windows only the readonly attribute
+    exists, so it is mapped to 0o444 (readonly) or 0o666 (writable).
+    """
+    try:
+        import clawlet_rust_core  # type: ignore
+
+        result = clawlet_rust_core.get_permissions(str(path))
+        if isinstance(result, tuple) and len(result) == 4:
+            return (bool(result[0]), int(result[1]), bool(result[2]), str(result[3]))
+    except Exception:
+        pass
+    return _get_permissions_python(path)
+
+
+def set_permissions(path: str, mode: int) -> tuple[bool, str]:
+    """
+    Set a file's permission bits via Rust core when available.
+
+    On Windows only the owner write bit is honoured: clearing it sets the readonly attribute.
+    """
+    try:
+        import clawlet_rust_core  # type: ignore
+
+        result = clawlet_rust_core.set_permissions(str(path), int(mode))
+        if isinstance(result, tuple) and len(result) == 2:
+            return (bool(result[0]), str(result[1]))
+    except Exception:
+        pass
+    return _set_permissions_python(path, mode)
+
+
+def execute_command_argv_bounded(
+    argv: list[str],
+    cwd: str,
+    timeout_seconds: float,
+    max_output_bytes: int,
+) -> tuple[bool, int, str, str, str, int, int]:
+    """
+    Execute command argv keeping only the last `max_output_bytes` of each stream.
+
+    Bytes are counted as they stream even when the middle is discarded, so callers
+    can report e.g. "showing last 10 KB of 4 MB".
+
+    Returns:
+      - tuple(success, returncode, stdout_tail, stderr_tail, error, stdout_total, stderr_total)
+    """
+    try:
+        import clawlet_rust_core  # type: ignore
+
+        result = clawlet_rust_core.execute_command_argv_bounded(
+            argv, cwd, float(timeout_seconds), int(max_output_bytes)
+        )
+        if isinstance(result, tuple) and len(result) == 7:
+            return (
+                bool(result[0]),
+                int(result[1]),
+                str(result[2]),
+                str(result[3]),
+                str(result[4]),
+                int(result[5]),
+                int(result[6]),
+            )
+    except Exception:
+        pass
+    return _execute_command_argv_bounded_python(argv, cwd, timeout_seconds, max_output_bytes)
+
+
+def read_line_ranges(
+    path: str,
+    ranges: list[tuple[int, int]],
+) -> tuple[bool, list[list[str]], str]:
+    """
+    Read several 1-based inclusive line ranges from a file in a single read.
+
+    Ranges are served independently in the order given, so overlapping or
+    out-of-order ranges are fine. Ranges running past EOF are clamped.
+    """
+    try:
+        import clawlet_rust_core  # type: ignore
+
+        normalized = [(int(start), int(end)) for start, end in ranges]
+        result = clawlet_rust_core.read_line_ranges(str(path), normalized)
+        if isinstance(result, tuple) and len(result) == 3:
+            groups = [[str(line) for line in group] for group in result[1]]
+            return (bool(result[0]), groups, str(result[2]))
+    except Exception:
+        pass
+    return _read_line_ranges_python(path, ranges)
+
+
+def hash_tree(
+    root: str,
+    respect_gitignore: bool = True,
+    ignore_extensions: Optional[list[str]] = None,
+) -> tuple[bool, str, str]:
+    """
+    Compute a combined SHA-256 digest of every file under a directory tree.
+
+    Files whose extension is in `ignore_extensions` (e.g. ".log", "tmp") are left out
+    so volatile files don't churn the digest. The normalized ignore set is itself fed
+    into the digest: it is part of the hash contract, and digests computed with
+    different ignore sets are never equal.
+    """
+    extensions = sorted(_normalize_extensions(ignore_extensions or []))
+    try:
+        import clawlet_rust_core  # type: ignore
+
+        result = clawlet_rust_core.hash_tree(str(root), bool(respect_gitignore), extensions)
+        if isinstance(result, tuple) and len(result) == 3:
+            return (bool(result[0]), str(result[1]), str(result[2]))
+    except Exception:
+        pass
+    return _hash_tree_python(root, respect_gitignore, extensions)
+
+
+def transform_file(path: str, callback: Callable[[str], str]) -> tuple[bool, int, str]:
+    """
+    Read-modify-write a UTF-8 file atomically through a Python callback.
+
+    The callback receives the current content and returns the new content. If it
+    raises (or returns a non-string) the file is left untouched.
+
+    Returns:
+      - tuple(ok, bytes_written, error)
+    """
+    try:
+        import clawlet_rust_core  # type: ignore
+
+        rust_transform = clawlet_rust_core.transform_file
+    except Exception:
+        rust_transform = None
+
+    if rust_transform is not None:
+        try:
+            result = rust_transform(str(path), callback)
+        except Exception as e:
+            return False, 0, f"Transform callback failed: {e}"
+        if isinstance(result, tuple) and len(result) == 3:
+            return (bool(result[0]), int(result[1]), str(result[2]))
+    return _transform_file_python(path, callback)
+
+
+def debug_open_handles() -> int:
+    """
+    Count child-process and file handles still held by the core (leak diagnostics).
+
+    With the Rust core this is its registry count; on the Python path it is the
+    number of child processes spawned by this module that have not been reaped,
+    including spawn_detached children until they exit. Processes started outside this
+    module (for example by the shell tool's own subprocess fallback) are not counted.
+    """
+    try:
+        import clawlet_rust_core  # type: ignore
+
+        return int(clawlet_rust_core.debug_open_handles())
+    except Exception:
+        return _debug_open_handles_python()
+
+
+def remap_line(patch: str, old_line: int) -> tuple[bool, Optional[int], str]:
+    """
+    Map a 1-based line number in the pre-patch file to its line after the patch.
+
+    Returns None for the new line when the old line was deleted by the patch.
+    """
+    try:
+        import clawlet_rust_core  # type: ignore
+
+        result = clawlet_rust_core.remap_line(str(patch), int(old_line))
+        if isinstance(result, tuple) and len(result) == 3:
+            new_line = None if result[1] is None else int(result[1])
+            return (bool(result[0]), new_line, str(result[2]))
+    except Exception:
+        pass
+    return _remap_line_python(patch, old_line)
+
+
+def chunk_text(content: str, window_lines: int, overlap_lines: int) -> list[tuple[int, int, str]]:
+    """
+    Split text into windows of `window_lines` lines that overlap by `overlap_lines`.
+
+    Returns (start_line, end_line, text) with 1-based inclusive line numbers; the final
+    window may be shorter. Raises ValueError unless 0 <= overlap_lines < window_lines.
+    """
+    if window_lines < 1 or overlap_lines < 0 or overlap_lines >= window_lines:
+        raise ValueError(
+            "chunk_text requires window_lines >= 1 and 0 <= overlap_lines < window_lines"
+        )
+    try:
+        import clawlet_rust_core  # type: ignore
+
+        result = clawlet_rust_core.chunk_text(str(content), int(window_lines), int(overlap_lines))
+        if isinstance(result, list):
+            return [(int(start), int(end), str(text)) for start, end, text in result]
+    except Exception:
+        pass
+    return _chunk_text_python(content, window_lines, overlap_lines)
 
-    if not saw_hunk:
-        return False, "Patch must contain at least one unified diff hunk (@@ ...)"
 
-    if expected_old > 0 and seen_old != expected_old:
-        return False, f"Old-side hunk line count mismatch: expected {expected_old}, got {seen_old}"
-    if expected_new > 0 and seen_new != expected_new:
-        return False, f"New-side hunk line count mismatch: expected {expected_new}, got {seen_new}"
+def reverse_patch(patch: str) -> tuple[bool, str, str]:
+    """Invert a unified diff (swap headers, hunk ranges and +/- lines) via Rust core."""
+    try:
+        import clawlet_rust_core  # type: ignore
+
+        result = clawlet_rust_core.reverse_patch(str(patch))
+        if isinstance(result, tuple) and len(result) == 3:
+            return (bool(result[0]), str(result[1]), str(result[2]))
+    except Exception:
+        pass
+    return _reverse_patch_python(patch)
+
+
+def is_patch_reversible(patch: str) -> tuple[bool, bool, str]:
+    """
+    Check whether reverse_patch yields a structurally valid inverse.
+
+    Returns:
+      - tuple(ok, reversible, reason) where ok is False only if the input patch itself is invalid
+    """
+    try:
+        import clawlet_rust_core  # type: ignore
+
+        result = clawlet_rust_core.is_patch_reversible(str(patch))
+        if isinstance(result, tuple) and len(result) == 3:
+            return (bool(result[0]), bool(result[1]), str(result[2]))
+    except Exception:
+        pass
+    return _is_patch_reversible_python(patch)
+
+
+def hash_parts(parts: list[str], separator: str) -> str:
+    """
+    SHA-256 of the parts fed in order with `separator` between consecutive parts.
+
+    The digest equals sha256(separator.join(parts)) without building the joined
+    string, so part order is significant: ["a", "b"] and ["b", "a"] differ.
+    """
+    try:
+        import clawlet_rust_core  # type: ignore
+
+        return str(clawlet_rust_core.hash_parts([str(part) for part in parts], str(separator)))
+    except Exception:
+        return _hash_parts_python(parts, separator)
+
+
+def read_text_strict(path: str) -> tuple[bool, str, str]:
+    """
+    Read a UTF-8 text file, failing on invalid UTF-8 instead of substituting U+FFFD.
+
+    read_text_file stays lossy for compatibility; use this when invalid data should
+    mean "treat as binary".
+    """
+    try:
+        import clawlet_rust_core  # type: ignore
+
+        result = clawlet_rust_core.read_text_strict(str(path))
+        if isinstance(result, tuple) and len(result) == 3:
+            return (bool(result[0]), str(result[1]), str(result[2]))
+    except Exception:
+        pass
+    return _read_text_strict_python(path)
+
+
+def build_manifest(root: str) -> tuple[bool, list[tuple[str, int, str]], str]:
+    """
+    Record (relpath, size, sha256) for every file under root, sorted by path.
+
+    Respects .gitignore and lists files only; the result is what a later manifest
+    verification checks the tree against.
+    """
+    try:
+        import clawlet_rust_core  # type: ignore
+
+        result = clawlet_rust_core.build_manifest(str(root))
+        if isinstance(result, tuple) and len(result) == 3:
+            entries = [(str(rel), int(size), str(digest)) for rel, size, digest in result[1]]
+            return (bool(result[0]), entries, str(result[2]))
+    except Exception:
+        pass
+    return _build_manifest_python(root)
+
+
+def execute_command_streaming(
+    argv: list[str],
+    cwd: str,
+    timeout_seconds: float,
+    callback: Callable[[str, str], object],
+    line_mode: bool = False,
+    max_line_bytes: int = 65536,
+) -> tuple[bool, int, str]:
+    """
+    Execute command argv, passing output to callback(stream, text) as it arrives.
+
+    In line_mode the callback gets one call per complete line (terminator stripped)
+    plus a final flush of any unterminated tail at EOF. Lines longer than
+    `max_line_bytes` are emitted in pieces so buffering stays bounded. A callback
+    exception kills the command and is reported as the error.
+
+    Returns:
+      - tuple(success, returncode, error)
+    """
+    try:
+        import clawlet_rust_core  # type: ignore
+
+        rust_streaming = clawlet_rust_core.execute_command_streaming
+    except Exception:
+        rust_streaming = None
+
+    if rust_streaming is not None:
+        try:
+            result = rust_streaming(
+                argv, cwd, float(timeout_seconds), callback, bool(line_mode), int(max_line_bytes)
+            )
+        except Exception as e:
+            return False, -1, f"Streaming callback failed: {e}"
+        if isinstance(result, tuple) and len(result) == 3:
+            return (bool(result[0]), int(result[1]), str(result[2]))
+    return _execute_command_streaming_python(
+        argv, cwd, timeout_seconds, callback, line_mode, max_line_bytes
+    )
+
+
+def minimize_patch(patch: str) -> tuple[bool, str, str]:
+    """
+    Strip git metadata (diff --git, index, mode lines, ...) down to a plain unified diff.
+
+    Only ---/+++ headers, @@ headers and hunk bodies are kept; the result is validated.
+    """
+    try:
+        import clawlet_rust_core  # type: ignore
+
+        result = clawlet_rust_core.minimize_patch(str(patch))
+        if isinstance(result, tuple) and len(result) == 3:
+            return (bool(result[0]), str(result[1]), str(result[2]))
+    except Exception:
+        pass
+    return _minimize_patch_python(patch)
+
+
+def line_hashes(path: str) -> tuple[bool, list[str], str]:
+    """SHA-256 of each line's raw bytes (terminator excluded), computed in one streaming pass."""
+    try:
+        import clawlet_rust_core  # type: ignore
+
+        result = clawlet_rust_core.line_hashes(str(path))
+        if isinstance(result, tuple) and len(result) == 3:
+            return (bool(result[0]), [str(digest) for digest in result[1]], str(result[2]))
+    except Exception:
+        pass
+    return _line_hashes_python(path)
+
+
+def execute_command_min_runtime(
+    argv: list[str],
+    cwd: str,
+    timeout_seconds: float,
+    min_seconds: float,
+) -> tuple[bool, int, str, str, str, float, bool]:
+    """
+    Execute command argv and flag runs that exited before `min_seconds`.
+
+    The command is never delayed; exited_too_fast only surfaces instant-crash cases
+    to retry logic.
+
+    Returns:
+      - tuple(success, returncode, stdout, stderr, error, elapsed_seconds, exited_too_fast)
+    """
+    try:
+        import clawlet_rust_core  # type: ignore
+
+        result = clawlet_rust_core.execute_command_min_runtime(
+            argv, cwd, float(timeout_seconds), float(min_seconds)
+        )
+        if isinstance(result, tuple) and len(result) == 7:
+            return (
+                bool(result[0]),
+                int(result[1]),
+                str(result[2]),
+                str(result[3]),
+                str(result[4]),
+                float(result[5]),
+                bool(result[6]),
+            )
+    except Exception:
+        pass
+    return _execute_command_min_runtime_python(argv, cwd, timeout_seconds, min_seconds)
+
+
+def diff_trees(
+    a_root: str,
+    b_root: str,
+) -> tuple[bool, list[str], list[str], list[str], str]:
+    """
+    Compare two directory snapshots by content hash, respecting .gitignore in both.
+
+    Returns:
+      - tuple(ok, added, removed, modified, error) with sorted relative paths
+    """
+    try:
+        import clawlet_rust_core  # type: ignore
+
+        result = clawlet_rust_core.diff_trees(str(a_root), str(b_root))
+        if isinstance(result, tuple) and len(result) == 5:
+            return (
+                bool(result[0]),
+                [str(p) for p in result[1]],
+                [str(p) for p in result[2]],
+                [str(p) for p in result[3]],
+                str(result[4]),
+            )
+    except Exception:
+        pass
+    return _diff_trees_python(a_root, b_root)
+
+
+def read_base64(path: str) -> tuple[bool, str, str]:
+    """Read a file's raw bytes as a base64 string via Rust core when available."""
+    try:
+        import clawlet_rust_core  # type: ignore
+
+        result = clawlet_rust_core.read_base64(str(path))
+        if isinstance(result, tuple) and len(result) == 3:
+            return (bool(result[0]), str(result[1]), str(result[2]))
+    except Exception:
+        pass
+    return _read_base64_python(path)
+
 
-    return True, "ok"
+def write_base64(path: str, data_b64: str) -> tuple[bool, int, str]:
+    """Decode base64 and write the raw bytes; invalid base64 is an error and writes nothing."""
+    try:
+        import clawlet_rust_core  # type: ignore
+
+        result = clawlet_rust_core.write_base64(str(path), str(data_b64))
+        if isinstance(result, tuple) and len(result) == 3:
+            return (bool(result[0]), int(result[1]), str(result[2]))
+    except Exception:
+        pass
+    return _write_base64_python(path, data_b64)
+
+
+def count_glob(root: str, pattern: str) -> tuple[bool, int, str]:
+    """
+    Count paths under root matching a glob without materializing the matches.
+
+    The pattern is matched against root-relative posix paths ("*.py" is top-level
+    only, "**/*.py" is recursive); files and directories both count.
+    """
+    try:
+        import clawlet_rust_core  # type: ignore
+
+        result = clawlet_rust_core.count_glob(str(root), str(pattern))
+        if isinstance(result, tuple) and len(result) == 3:
+            return (bool(result[0]), int(result[1]), str(result[2]))
+    except Exception:
+        pass
+    return _count_glob_python(root, pattern)
+
+
+def format_patch(patch: str, context: Optional[int] = None) -> tuple[bool, str, str]:
+    """
+    Validate a patch and re-emit it in canonical form for display.
+
+    Hunk headers are recomputed from the bodies (always "@@ -a,b +c,d @@") and
+    trailing whitespace on header lines is dropped. With `context`, each hunk keeps
+    at most that many context lines around its changes and is split where more than
+    2 * context unchanged lines separate them.
+    """
+    try:
+        import clawlet_rust_core  # type: ignore
+
+        rust_context = None if context is None else int(context)
+        result = clawlet_rust_core.format_patch(str(patch), rust_context)
+        if isinstance(result, tuple) and len(result) == 3:
+            return (bool(result[0]), str(result[1]), str(result[2]))
+    except Exception:
+        pass
+    return _format_patch_python(patch, context)
+
+
+def spawn_detached(
+    argv: list[str],
+    cwd: str,
+    log_path: Optional[str] = None,
+) -> tuple[bool, int, str]:
+    """
+    Start a long-lived process detached from the agent and return its pid.
+
+    The child runs in its own session (process group on Windows) with stdin from the
+    null device and stdout/stderr appended to `log_path` or discarded. Unlike the
+    execute_* helpers nothing ties its lifetime to the caller; manage it by pid.
+    """
+    try:
+        import clawlet_rust_core  # type: ignore
+
+        rust_log_path = None if log_path is None else str(log_path)
+        result = clawlet_rust_core.spawn_detached(argv, cwd, rust_log_path)
+        if isinstance(result, tuple) and len(result) == 3:
+            return (bool(result[0]), int(result[1]), str(result[2]))
+    except Exception:
+        pass
+    return _spawn_detached_python(argv, cwd, log_path)
+
+
+def hunks_status(path: str, patch: str) -> tuple[bool, list[tuple[int, bool]], str]:
+    """
+    Report, per hunk (0-based index), whether it applies cleanly to the file as it is now.
+
+    Each hunk is checked independently: its context and removed lines must match the
+    file exactly at the hunk's stated old-side position.
+    """
+    try:
+        import clawlet_rust_core  # type: ignore
+
+        result = clawlet_rust_core.hunks_status(str(path), str(patch))
+        if isinstance(result, tuple) and len(result) == 3:
+            statuses = [(int(index), bool(applies)) for index, applies in result[1]]
+            return (bool(result[0]), statuses, str(result[2]))
+    except Exception:
+        pass
+    return _hunks_status_python(path, patch)
+
+
+def read_text_bom(path: str) -> tuple[bool, str, bool, str]:
+    """
+    Read a UTF-8 text file with any leading BOM stripped, reporting whether one was present.
+
+    Returns:
+      - tuple(ok, content, had_bom, error)
+    """
+    try:
+        import clawlet_rust_core  # type: ignore
+
+        result = clawlet_rust_core.read_text_bom(str(path))
+        if isinstance(result, tuple) and len(result) == 4:
+            return (bool(result[0]), str(result[1]), bool(result[2]), str(result[3]))
+    except Exception:
+        pass
+    return _read_text_bom_python(path)
+
+
+def check_argv_paths(argv: list[str], cwd: str) -> tuple[bool, list[str], str]:
+    """
+    List arguments that look like paths but don't exist, so typos surface before running.
+
+    The heuristic is deliberately conservative: the program name, flags, KEY=VALUE
+    pairs, URLs and glob patterns are skipped, and only arguments containing a path
+    separator are treated as paths (resolved against cwd). ok is False if any is missing.
+    """
+    try:
+        import clawlet_rust_core  # type: ignore
+
+        result = clawlet_rust_core.check_argv_paths(argv, cwd)
+        if isinstance(result, tuple) and len(result) == 3:
+            return (bool(result[0]), [str(arg) for arg in result[1]], str(result[2]))
+    except Exception:
+        pass
+    return _check_argv_paths_python(argv, cwd)
+
+
+def hash_with_previous(
+    path: str,
+    previous_hash_state: bytes,
+    from_offset: int,
+) -> tuple[bool, str, bytes, str]:
+    """
+    Resume a SHA-256 over an append-only file from a saved state instead of rehashing.
+
+    Pass an empty state with from_offset=0 to start. The returned state must be saved
+    and passed back with the offset it covers (the file size at the time). State layout:
+    8 big-endian u32 chaining words, a big-endian u64 byte count, then the 0-63 byte
+    unprocessed tail.
+
+    Without the Rust core the hashing runs in pure Python, because hashlib cannot export
+    its state. That manages well under 1 MB/s, so a multi-MB log takes seconds to
+    minutes even from offset 0. If you only need a digest, use hash_and_size instead.
+
+    Returns:
+      - tuple(ok, hex_digest, new_state, error)
+    """
+    try:
+        import clawlet_rust_core  # type: ignore
+
+        result = clawlet_rust_core.hash_with_previous(
+            str(path), bytes(previous_hash_state), int(from_offset)
+        )
+        if isinstance(result, tuple) and len(result) == 4:
+            return (bool(result[0]), str(result[1]), bytes(result[2]), str(result[3]))
+    except Exception:
+        pass
+    return _hash_with_previous_python(path, previous_hash_state, from_offset)
+
+
+def execute_hermetic(
+    argv: list[str],
+    cwd: str,
+    timeout_seconds: float,
+    path_dirs: list[str],
+    extra_env: list[tuple[str, str]],
+) -> tuple[bool, int, str, str, str]:
+    """
+    Execute command argv in a cleared environment for reproducible runs.
+
+    Nothing is inherited from the host: PATH is `path_dirs` joined with os.pathsep
+    (and is also what resolves argv[0]), then `extra_env` is applied on top.
+
+    Returns:
+      - tuple(success, returncode, stdout, stderr, error)
+    """
+    try:
+        import clawlet_rust_core  # type: ignore
+
+        result = clawlet_rust_core.execute_hermetic(
+            argv,
+            cwd,
+            float(timeout_seconds),
+            [str(d) for d in path_dirs],
+            [(str(k), str(v)) for k, v in extra_env],
+        )
+        if isinstance(result, tuple) and len(result) == 5:
+            return (
+                bool(result[0]),
+                int(result[1]),
+                str(result[2]),
+                str(result[3]),
+                str(result[4]),
+            )
+    except Exception:
+        pass
+    return _execute_hermetic_python(argv, cwd, timeout_seconds, path_dirs, extra_env)
+
+
+def git_blob_hash(path: str) -> tuple[bool, str, str]:
+    """Compute git's blob object id, sha1("blob <len>\\0" + content), without invoking git."""
+    try:
+        import clawlet_rust_core  # type: ignore
+
+        result = clawlet_rust_core.git_blob_hash(str(path))
+        if isinstance(result, tuple) and len(result) == 3:
+            return (bool(result[0]), str(result[1]), str(result[2]))
+    except Exception:
+        pass
+    return _git_blob_hash_python(path)
+
+
+def tokenize_words(content: str) -> list[tuple[str, int, int]]:
+    """
+    Split text into identifier-like words with UTF-8 byte offsets.
+
+    A word is a letter or underscore followed by letters, digits or underscores
+    (Unicode-aware). Returns (word, start_byte, end_byte) with end exclusive.
+    """
+    try:
+        import clawlet_rust_core  # type: ignore
+
+        result = clawlet_rust_core.tokenize_words(str(content))
+        if isinstance(result, list):
+            return [(str(word), int(start), int(end)) for word, start, end in result]
+    except Exception:
+        pass
+    return _tokenize_words_python(content)
+
+
+def secure_delete(path: str) -> tuple[bool, str]:
+    """
+    Overwrite a file with zeros (one pass), flush it to disk, then remove it.
+
+    Best effort only: on SSDs (wear levelling) and copy-on-write or journaling
+    filesystems the original blocks may survive. Use it for scratch files that held
+    secrets, not as a forensic guarantee.
+    """
+    try:
+        import clawlet_rust_core  # type: ignore
+
+        result = clawlet_rust_core.secure_delete(str(path))
+        if isinstance(result, tuple) and len(result) == 2:
+            return (bool(result[0]), str(result[1]))
+    except Exception:
+        pass
+    return _secure_delete_python(path)
+
+
+def detect_moves(
+    old: str,
+    new: str,
+    min_block_lines: int,
+) -> tuple[bool, list[tuple[int, int, int]], str]:
+    """
+    Find blocks of at least `min_block_lines` lines that moved rather than changed.
+
+    A move is a run of lines the line diff reports as deleted from `old` that
+    reappears verbatim as an inserted run in `new`. Returns 1-based
+    (old_start, new_start, length) blocks in old-file order.
+    """
+    try:
+        import clawlet_rust_core  # type: ignore
+
+        result = clawlet_rust_core.detect_moves(str(old), str(new), int(min_block_lines))
+        if isinstance(result, tuple) and len(result) == 3:
+            blocks = [(int(a), int(b), int(n)) for a, b, n in result[1]]
+            return (bool(result[0]), blocks, str(result[2]))
+    except Exception:
+        pass
+    return _detect_moves_python(old, new, min_block_lines)
+
+
+def read_text_retry(path: str, max_retries: int, backoff_ms: int) -> tuple[bool, str, str]:
+    """
+    Read a UTF-8 text file, retrying transient failures with exponential backoff.
+
+    Only interrupted, would-block and timed-out errors are retried (up to
+    `max_retries` times, sleeping backoff_ms, 2*backoff_ms, ...). Errors such as
+    not-found or permission-denied fail immediately since retrying won't help.
+    """
+    try:
+        import clawlet_rust_core  # type: ignore
+
+        result = clawlet_rust_core.read_text_retry(str(path), int(max_retries), int(backoff_ms))
+        if isinstance(result, tuple) and len(result) == 3:
+            return (bool(result[0]), str(result[1]), str(result[2]))
+    except Exception:
+        pass
+    return _read_text_retry_python(path, max_retries, backoff_ms)
+
+
+def list_dir_detailed(path: str) -> tuple[bool, list[tuple[str, bool, int, float, bool]], str]:
+    """
+    List directory entries with metadata in one pass, sorted by name.
+
+    Each entry is (name, is_dir, size, mtime_unix, is_symlink). Metadata describes the
+    entry itself: symlinks are not followed, so a link to a directory has is_dir False.
+    """
+    try:
+        import clawlet_rust_core  # type: ignore
+
+        result = clawlet_rust_core.list_dir_detailed(str(path))
+        if isinstance(result, tuple) and len(result) == 3:
+            entries = [
+                (str(name), bool(is_dir), int(size), float(mtime), bool(is_link))
+                for name, is_dir, size, mtime, is_link in result[1]
+            ]
+            return (bool(result[0]), entries, str(result[2]))
+    except Exception:
+        pass
+    return _list_dir_detailed_python(path)
+
+
+def apply_string_edit(path: str, old_string: str, new_string: str) -> tuple[bool, str]:
+    """
+    Replace the single occurrence of `old_string` in a file and write it atomically.
+
+    Fails without writing if `old_string` is absent or occurs more than once; the
+    error says which, so the caller can widen the snippet.
+    """
+    try:
+        import clawlet_rust_core  # type: ignore
+
+        result = clawlet_rust_core.apply_string_edit(str(path), str(old_string), str(new_string))
+        if isinstance(result, tuple) and len(result) == 2:
+            return (bool(result[0]), str(result[1]))
+    except Exception:
+        pass
+    return _apply_string_edit_python(path, old_string, new_string)
+
+
+def count_occurrences_in_file(path: str, needle: str) -> tuple[bool, int, str]:
+    """
+    Count non-overlapping occurrences of `needle` in a file without loading it whole.
+
+    Matches spanning read-chunk boundaries are counted; pair with apply_string_edit to
+    grow a snippet until it is unique.
+    """
+    try:
+        import clawlet_rust_core  # type: ignore
+
+        result = clawlet_rust_core.count_occurrences_in_file(str(path), str(needle))
+        if isinstance(result, tuple) and len(result) == 3:
+            return (bool(result[0]), int(result[1]), str(result[2]))
+    except Exception:
+        pass
+    return _count_occurrences_in_file_python(path, needle)
+
+
+def apply_string_edits(
+    path: str,
+    edits: list[tuple[str, str]],
+) -> tuple[bool, int, str]:
+    """
+    Apply several `(old_string, new_string)` edits to one file and write it once.
+
+    Each edit runs against the content left by the previous one and must match uniquely.
+    On failure nothing is written; the count is the number of edits that succeeded before
+    the failing one, and the error names its index.
+    """
+    try:
+        import clawlet_rust_core  # type: ignore
+
+        result = clawlet_rust_core.apply_string_edits(
+            str(path), [(str(old), str(new)) for old, new in edits]
+        )
+        if isinstance(result, tuple) and len(result) == 3:
+            return (bool(result[0]), int(result[1]), str(result[2]))
+    except Exception:
+        pass
+    return _apply_string_edits_python(path, edits)
+
+
+def string_edits_to_patch(
+    path: str,
+    edits: list[tuple[str, str]],
+    context: int = 3,
+) -> tuple[bool, str, str]:
+    """
+    Preview `apply_string_edits` as a unified diff without touching the file.
+
+    The edits are validated exactly as `apply_string_edits` would validate them, so a
+    successful preview means the real apply will succeed against unchanged content.
+    """
+    try:
+        import clawlet_rust_core  # type: ignore
+
+        result = clawlet_rust_core.string_edits_to_patch(
+            str(path), [(str(old), str(new)) for old, new in edits], int(context)
+        )
+        if isinstance(result, tuple) and len(result) == 3:
+            return (bool(result[0]), str(result[1]), str(result[2]))
+    except Exception:
+        pass
+    return _string_edits_to_patch_python(path, edits, context)
+
+
+def latest_mtime(root: str) -> tuple[bool, float, str]:
+    """
+    Return the newest file mtime (unix seconds) under root, respecting .gitignore.
+
+    A cheap change probe: if it has not moved since the last index, a full re-hash can
+    be skipped. An empty tree reports 0.0.
+    """
+    try:
+        import clawlet_rust_core  # type: ignore
+
+        result = clawlet_rust_core.latest_mtime(str(root))
+        if isinstance(result, tuple) and len(result) == 3:
+            return (bool(result[0]), float(result[1]), str(result[2]))
+    except Exception:
+        pass
+    return _latest_mtime_python(root)
+
+
+def execute_command_to_queue(
+    argv: list[str],
+    cwd: str,
+    timeout_seconds: float,
+    queue: object,
+) -> tuple[bool, int, str]:
+    """
+    Execute command argv, putting (stream, text) tuples on `queue` as output arrives.
+
+    `queue` only needs a `put` method (queue.Queue, or an asyncio queue's thread-safe
+    wrapper). A final `None` sentinel is put once the command finishes, fails or times
+    out. Both pipes are drained concurrently, so a quiet stream never stalls the child.
+
+    Returns:
+      - tuple(success, returncode, error)
+    """
+    try:
+        import clawlet_rust_core  # type: ignore
+
+        rust_to_queue = clawlet_rust_core.execute_command_to_queue
+    except Exception:
+        rust_to_queue = None
+
+    if rust_to_queue is not None:
+        try:
+            result = rust_to_queue(argv, cwd, float(timeout_seconds), queue)
+        except Exception as e:
+            queue.put(None)  # type: ignore[attr-defined]
+            return False, -1, f"Queue delivery failed: {e}"
+        if isinstance(result, tuple) and len(result) == 3:
+            return (bool(result[0]), int(result[1]), str(result[2]))
+    return _execute_command_to_queue_python(argv, cwd, timeout_seconds, queue)
+
+
+class HashCache:
+    """
+    SHA-256 file digests cached by (mtime, size); unchanged files are not re-read.
+
+    Safe to share between threads. Uses the Rust implementation when available.
+    """
+
+    def __init__(self) -> None:
+        self._rust = None
+        try:
+            import clawlet_rust_core  # type: ignore
+
+            self._rust = clawlet_rust_core.HashCache()
+        except Exception:
+            self._rust = None
+        self._entries: dict[str, tuple[int, int, str]] = {}
+        self._lock = threading.Lock()
+
+    def get(self, path: str) -> tuple[bool, str, str]:
+        """Return (ok, hex digest, error), hashing only if the file changed since last seen."""
+        if self._rust is not None:
+            try:
+                result = self._rust.get(str(path))
+                if isinstance(result, tuple) and len(result) == 3:
+                    return (bool(result[0]), str(result[1]), str(result[2]))
+            except Exception:
+                pass
+        return self._get_python(str(path))
+
+    def _get_python(self, path: str) -> tuple[bool, str, str]:
+        try:
+            st = os.stat(path)
+        except OSError as e:
+            with self._lock:
+                self._entries.pop(path, None)
+            return False, "", str(e)
+        with self._lock:
+            cached = self._entries.get(path)
+        if cached is not None and cached[:2] == (st.st_mtime_ns, st.st_size):
+            return True, cached[2], ""
+        try:
+            digest = _sha256_file(path)
+            after = os.stat(path)
+        except OSError as e:
+            return False, "", str(e)
+        # A write during hashing means the digest may not match either stat; don't cache it.
+        if (after.st_mtime_ns, after.st_size) == (st.st_mtime_ns, st.st_size):
+            with self._lock:
+                self._entries[path] = (st.st_mtime_ns, st.st_size, digest)
+        return True, digest, ""
+
+
+def validate_patch_against_file(path: str, patch: str) -> tuple[bool, str]:
+    """
+    Validate a patch structurally and against the file it targets.
+
+    Each hunk's old-side range must lie within the file and its context and removed
+    lines must match at the stated position. Returns (True, "ok") or the first problem.
+    """
+    try:
+        import clawlet_rust_core  # type: ignore
+
+        result = clawlet_rust_core.validate_patch_against_file(str(path), str(patch))
+        if isinstance(result, tuple) and len(result) == 2:
+            return (bool(result[0]), str(result[1]))
+    except Exception:
+        pass
+    return _validate_patch_against_file_python(path, patch)
+
+
+def truncate_to_last_bytes(path: str, max_bytes: int) -> tuple[bool, int, str]:
+    """
+    Shrink a file to at most its last `max_bytes`, rewriting it atomically.
+
+    The kept tail starts at a line boundary so no partial first line survives, unless
+    the tail holds no newline at all. Files already within the cap are left untouched.
+    Returns the resulting size.
+    """
+    try:
+        import clawlet_rust_core  # type: ignore
+
+        result = clawlet_rust_core.truncate_to_last_bytes(str(path), int(max_bytes))
+        if isinstance(result, tuple) and len(result) == 3:
+            return (bool(result[0]), int(result[1]), str(result[2]))
+    except Exception:
+        pass
+    return _truncate_to_last_bytes_python(path, max_bytes)
+
+
+def read_expanded(path: str, tab_width: int = 8) -> tuple[bool, str, str]:
+    """
+    Read a text file with tabs expanded to the next multiple of `tab_width` columns.
+
+    Columns are display columns: wide (CJK) characters count as two and combining marks
+    as zero, so alignment after non-ASCII text is preserved.
+    """
+    try:
+        import clawlet_rust_core  # type: ignore
+
+        result = clawlet_rust_core.read_expanded(str(path), int(tab_width))
+        if isinstance(result, tuple) and len(result) == 3:
+            return (bool(result[0]), str(result[1]), str(result[2]))
+    except Exception:
+        pass
+    return _read_expanded_python(path, tab_width)
+
+
+def diff_ops(old: str, new: str) -> list[tuple[str, int, int, str]]:
+    """
+    Describe the line diff from `old` to `new` as (op, at_line, count, text) operations.
+
+    `op` is "insert", "delete" or "replace"; `at_line` is the 1-based line in `old`
+    (for inserts, the line the text goes before); `count` is how many old lines are
+    removed (0 for inserts); `text` is the new lines with their terminators. Operations
+    are in ascending order and all positions refer to `old`, so apply them bottom-up.
+    """
+    try:
+        import clawlet_rust_core  # type: ignore
+
+        result = clawlet_rust_core.diff_ops(str(old), str(new))
+        if isinstance(result, list):
+            return [(str(op), int(at), int(count), str(text)) for op, at, count, text in result]
+    except Exception:
+        pass
+    return _diff_ops_python(old, new)
+
+
+def read_snapshot(path: str, max_retries: int = 3) -> tuple[bool, str, int, str]:
+    """
+    Read a file that may be growing and return (ok, content, size, error).
+
+    The size is checked before and after the read; if it moved, the read is retried up
+    to `max_retries` times so the content always matches the reported size.
+    """
+    try:
+        import clawlet_rust_core  # type: ignore
+
+        result = clawlet_rust_core.read_snapshot(str(path), int(max_retries))
+        if isinstance(result, tuple) and len(result) == 4:
+            return (bool(result[0]), str(result[1]), int(result[2]), str(result[3]))
+    except Exception:
+        pass
+    return _read_snapshot_python(path, max_retries)
+
+
+def offset_to_position(content: str, offset: int) -> tuple[bool, int, int, str]:
+    """
+    Convert a UTF-8 byte offset in `content` to a 1-based (line, column).
+
+    Columns count Unicode scalar values, not bytes. `offset` may equal the byte length
+    (end of text) but must not exceed it or fall inside a multibyte character.
+    """
+    try:
+        import clawlet_rust_core  # type: ignore
+
+        result = clawlet_rust_core.offset_to_position(str(content), int(offset))
+        if isinstance(result, tuple) and len(result) == 4:
+            return (bool(result[0]), int(result[1]), int(result[2]), str(result[3]))
+    except Exception:
+        pass
+    return _offset_to_position_python(content, offset)
+
+
+def position_to_offset(content: str, line: int, column: int) -> tuple[bool, int, str]:
+    """
+    Convert a 1-based (line, column) in `content` to a UTF-8 byte offset.
+
+    The inverse of offset_to_position. A column past the end of the line is clamped to
+    the line's end (just before its "\n" or "\r\n"); a line past the end of the text is an
+    error.
+    """
+    try:
+        import clawlet_rust_core  # type: ignore
+
+        result = clawlet_rust_core.position_to_offset(str(content), int(line), int(column))
+        if isinstance(result, tuple) and len(result) == 3:
+            return (bool(result[0]), int(result[1]), str(result[2]))
+    except Exception:
+        pass
+    return _position_to_offset_python(content, line, column)
+
+
+def ends_with_newline(path: str) -> tuple[bool, bool, str]:
+    """
+    Report whether a file's last byte is a newline, reading only that byte.
+
+    Empty files report False.
+    """
+    try:
+        import clawlet_rust_core  # type: ignore
+
+        result = clawlet_rust_core.ends_with_newline(str(path))
+        if isinstance(result, tuple) and len(result) == 3:
+            return (bool(result[0]), bool(result[1]), str(result[2]))
+    except Exception:
+        pass
+    return _ends_with_newline_python(path)
+
+
+def execute_command_status(
+    argv: list[str],
+    cwd: str,
+    timeout_seconds: float,
+) -> tuple[str, int, int, str, str]:
+    """
+    Execute command argv and classify how it ended.
+
+    Returns (status_kind, exit_code, signal, stdout, stderr) where status_kind is one of
+    "ok", "failed" (nonzero exit), "timeout", "signal" (killed by `signal`) or
+    "spawn_error" (stderr holds the reason). exit_code is -1 whenever there is no exit
+    status and signal is 0 unless status_kind is "signal".
+    """
+    try:
+        import clawlet_rust_core  # type: ignore
+
+        result = clawlet_rust_core.execute_command_status(argv, cwd, float(timeout_seconds))
+        if isinstance(result, tuple) and len(result) == 5:
+            return (str(result[0]), int(result[1]), int(result[2]), str(result[3]), str(result[4]))
+    except Exception:
+        pass
+    return _execute_command_status_python(argv, cwd, timeout_seconds)
+
+
+def find_symlinks(root: str) -> tuple[bool, list[tuple[str, str]], str]:
+    """
+    List every symlink under root as (relative link path, raw target), sorted by path.
+
+    Links are never followed, so symlinked directories are reported but not entered.
+    Targets are returned as stored (possibly relative or dangling). Respects .gitignore.
+    """
+    try:
+        import clawlet_rust_core  # type: ignore
+
+        result = clawlet_rust_core.find_symlinks(str(root))
+        if isinstance(result, tuple) and len(result) == 3:
+            links = [(str(link), str(target)) for link, target in result[1]]
+            return (bool(result[0]), links, str(result[2]))
+    except Exception:
+        pass
+    return _find_symlinks_python(root)
+
+
+def preview_patch_on_file(path: str, patch: str, context: int = 3) -> tuple[bool, str, str]:
+    """
+    Apply a patch to a file in memory and return a fresh unified diff of the result.
+
+    The diff is regenerated from the real before/after text, labelled with `path` and
+    using `context` lines, so it both normalizes the patch and proves it applies. Nothing
+    is written; a patch that does not apply is reported as an error.
+    """
+    try:
+        import clawlet_rust_core  # type: ignore
+
+        result = clawlet_rust_core.preview_patch_on_file(str(path), str(patch), int(context))
+        if isinstance(result, tuple) and len(result) == 3:
+            return (bool(result[0]), str(result[1]), str(result[2]))
+    except Exception:
+        pass
+    return _preview_patch_on_file_python(path, patch, context)
+
+
+def merge3_preview(base: str, ours: str, theirs: str) -> tuple[bool, str, int, str]:
+    """
+    Three-way merge `ours` and `theirs` against `base`, reporting the conflict count.
+
+    Non-overlapping changes from either side are taken as-is and identical changes are
+    taken once. Each remaining conflict is written diff3-style with `<<<<<<< ours`,
+    `||||||| base`, `=======` and `>>>>>>> theirs` markers so the base is visible too.
+    """
+    try:
+        import clawlet_rust_core  # type: ignore
+
+        result = clawlet_rust_core.merge3_preview(str(base), str(ours), str(theirs))
+        if isinstance(result, tuple) and len(result) == 4:
+            return (bool(result[0]), str(result[1]), int(result[2]), str(result[3]))
+    except Exception:
+        pass
+    merged, conflicts = _merge3_python(base, ours, theirs)
+    return True, merged, conflicts, ""
+
+
+def execute_parallel(
+    commands: list[list[str]],
+    cwd: str,
+    timeout_seconds: float,
+    max_concurrency: int,
+) -> list[tuple[bool, int, str, str, str]]:
+    """
+    Run independent argv commands with at most `max_concurrency` in flight.
+
+    Each command is captured, timed out and killed exactly as with execute_command_argv.
+    Results are (success, returncode, stdout, stderr, error) tuples in input order.
+    """
+    try:
+        import clawlet_rust_core  # type: ignore
+
+        result = clawlet_rust_core.execute_parallel(
+            [list(argv) for argv in commands], cwd, float(timeout_seconds), int(max_concurrency)
+        )
+        if isinstance(result, list) and len(result) == len(commands):
+            return [
+                (bool(ok), int(code), str(stdout), str(stderr), str(error))
+                for ok, code, stdout, stderr, error in result
+            ]
+    except Exception:
+        pass
+    return _execute_parallel_python(commands, cwd, timeout_seconds, max_concurrency)
+
+
+def read_with_language(path: str) -> tuple[bool, str, str, str]:
+    """
+    Read a UTF-8 text file and detect its language in one call.
+
+    Returns (ok, content, language, error). The language comes from the file name or
+    extension, then the shebang line; unknown files report "text".
+    """
+    try:
+        import clawlet_rust_core  # type: ignore
+
+        result = clawlet_rust_core.read_with_language(str(path))
+        if isinstance(result, tuple) and len(result) == 4:
+            return (bool(result[0]), str(result[1]), str(result[2]), str(result[3]))
+    except Exception:
+        pass
+    return _read_with_language_python(path)
+
+
+def verify_manifest(
+    root: str,
+    manifest: list[tuple[str, int, str]],
+    respect_gitignore: bool = True,
+) -> tuple[bool, list[str], list[str], list[str], str]:
+    """
+    Reconcile a tree against a build_manifest result.
+
+    Returns (ok, missing, mismatched, extra, error): manifest paths absent from disk,
+    paths whose size or sha256 differ, and files on disk the manifest does not list.
+    With `respect_gitignore`, gitignored paths are neither required nor reported as
+    extra. Files are hashed in parallel, and only when their size already matches.
+    """
+    try:
+        import clawlet_rust_core  # type: ignore
+
+        result = clawlet_rust_core.verify_manifest(
+            str(root),
+            [(str(rel), int(size), str(digest)) for rel, size, digest in manifest],
+            bool(respect_gitignore),
+        )
+        if isinstance(result, tuple) and len(result) == 5:
+            return (
+                bool(result[0]),
+                [str(rel) for rel in result[1]],
+                [str(rel) for rel in result[2]],
+                [str(rel) for rel in result[3]],
+                str(result[4]),
+            )
+    except Exception:
+        pass
+    return _verify_manifest_python(root, manifest, respect_gitignore)
+
+
+def read_without_comments(
+    path: str,
+    comment_prefix: str,
+    keep_blank_lines: bool = True,
+) -> tuple[bool, str, str]:
+    """
+    Read a text file, dropping lines whose first non-whitespace text is `comment_prefix`.
+
+    Trailing comments after content are kept. Blank lines survive unless
+    `keep_blank_lines` is False; line endings are preserved.
+    """
+    try:
+        import clawlet_rust_core  # type: ignore
+
+        result = clawlet_rust_core.read_without_comments(
+            str(path), str(comment_prefix), bool(keep_blank_lines)
+        )
+        if isinstance(result, tuple) and len(result) == 3:
+            return (bool(result[0]), str(result[1]), str(result[2]))
+    except Exception:
+        pass
+    return _read_without_comments_python(path, comment_prefix, keep_blank_lines)
+
+
+def patch_coverage(path: str, patch: str) -> tuple[bool, float, str]:
+    """
+    Estimate how much of a file a patch rewrites, as a fraction in [0.0, 1.0].
+
+    Added plus removed lines are counted against the line count the file will have after
+    the patch (`path` is the unpatched file). A replaced line counts twice, so the value
+    is capped at 1.0; a patch that empties the file reports 1.0.
+    """
+    try:
+        import clawlet_rust_core  # type: ignore
+
+        result = clawlet_rust_core.patch_coverage(str(path), str(patch))
+        if isinstance(result, tuple) and len(result) == 3:
+            return (bool(result[0]), float(result[1]), str(result[2]))
+    except Exception:
+        pass
+    return _patch_coverage_python(path, patch)
+
+
+def execute_until_pattern(
+    argv: list[str],
+    cwd: str,
+    timeout_seconds: float,
+    abort_pattern: str,
+) -> tuple[bool, int, str, str, str, bool, str]:
+    """
+    Execute command argv, killing it as soon as an output line matches `abort_pattern`.
+
+    Lines from stdout and stderr are searched with the regex as they complete; lines
+    over 64 KiB are searched in 64 KiB pieces so buffering stays bounded. Output up to
+    and including the matching chunk is returned. A final unterminated line is checked
+    at EOF, after the process has exited: a match there is reported in matched_line
+    with the real exit status and aborted False, since nothing was killed.
+
+    Returns:
+      - tuple(success, returncode, stdout, stderr, error, aborted, matched_line)
+    """
+    try:
+        import clawlet_rust_core  # type: ignore
+
+        result = clawlet_rust_core.execute_until_pattern(
+            argv, cwd, float(timeout_seconds), str(abort_pattern)
+        )
+        if isinstance(result, tuple) and len(result) == 7:
+            return (
+                bool(result[0]),
+                int(result[1]),
+                str(result[2]),
+                str(result[3]),
+                str(result[4]),
+                bool(result[5]),
+                str(result[6]),
+            )
+    except Exception:
+        pass
+    return _execute_until_pattern_python(argv, cwd, timeout_seconds, abort_pattern)
+
+
+def ranges_to_hunks(
+    old: str,
+    new: str,
+    ranges: list[tuple[int, int]],
+    context: int = 3,
+) -> tuple[bool, str, str]:
+    """
+    Diff `old` against `new` but keep only hunks touching the given old-side line ranges.
+
+    Ranges are 1-based inclusive (start, end) pairs. Changes closer than 2 * `context`
+    lines share a hunk, as in a normal unified diff. New-side line numbers account for
+    the hunks left out, so the result applies cleanly to `old` on its own.
+    """
+    try:
+        import clawlet_rust_core  # type: ignore
+
+        result = clawlet_rust_core.ranges_to_hunks(
+            str(old), str(new), [(int(a), int(b)) for a, b in ranges], int(context)
+        )
+        if isinstance(result, tuple) and len(result) == 3:
+            return (bool(result[0]), str(result[1]), str(result[2]))
+    except Exception:
+        pass
+    return _ranges_to_hunks_python(old, new, ranges, context)
+
+
+def find_duplicate_lines(
+    path: str,
+    min_run: int,
+) -> tuple[bool, list[tuple[int, int, int]], str]:
+    """
+    Find blocks of at least `min_run` consecutive lines repeated later in a file.
+
+    Returns 1-based (first_start, second_start, length) runs, each extended as far as the
+    copies keep matching. Blocks made only of blank lines are ignored. Lines are kept as
+    64-bit digests rather than text, but the pure-Python path still holds one digest and
+    one window hash per line, so it refuses files over 1,000,000 lines with an error.
+    """
+    try:
+        import clawlet_rust_core  # type: ignore
+
+        result = clawlet_rust_core.find_duplicate_lines(str(path), int(min_run))
+        if isinstance(result, tuple) and len(result) == 3:
+            runs = [(int(a), int(b), int(n)) for a, b, n in result[1]]
+            return (bool(result[0]), runs, str(result[2]))
+    except Exception:
+        pass
+    return _find_duplicate_lines_python(path, min_run)
+
+
+def patch_max_line(patch: str) -> tuple[bool, int, int, str]:
+    """
+    Return the highest old-side and new-side line numbers a patch's hunks reference.
+
+    Computed from the hunk headers alone, so a patch aimed far past the end of its
+    target can be rejected before the file is read. Pure insertions reference their
+    anchor line.
+    """
+    try:
+        import clawlet_rust_core  # type: ignore
+
+        result = clawlet_rust_core.patch_max_line(str(patch))
+        if isinstance(result, tuple) and len(result) == 4:
+            return (bool(result[0]), int(result[1]), int(result[2]), str(result[3]))
+    except Exception:
+        pass
+    return _patch_max_line_python(patch)
+
+
+def list_modified_since(root: str, since_unix: float) -> tuple[bool, list[str], str]:
+    """
+    List relative paths of files under root with an mtime strictly after `since_unix`.
+
+    Respects .gitignore; paths are sorted. Pairs with latest_mtime for incremental
+    re-indexing.
+    """
+    try:
+        import clawlet_rust_core  # type: ignore
+
+        result = clawlet_rust_core.list_modified_since(str(root), float(since_unix))
+        if isinstance(result, tuple) and len(result) == 3:
+            return (bool(result[0]), [str(rel) for rel in result[1]], str(result[2]))
+    except Exception:
+        pass
+    return _list_modified_since_python(root, since_unix)
+
+
+def diff_trees_renames(
+    a_root: str,
+    b_root: str,
+    similarity: float = 1.0,
+) -> tuple[bool, list[tuple[str, str]], list[str], list[str], list[str], str]:
+    """
+    Like diff_trees, but pair removed and added files that are renames of each other.
+
+    Identical content always counts as a rename. With `similarity` below 1.0, remaining
+    pairs whose line similarity reaches it are matched too, best match first. Returns
+    (ok, [(old, new)], added, removed, modified, error); renamed paths are not repeated
+    in added/removed. `similarity` must be in (0, 1].
+    """
+    try:
+        import clawlet_rust_core  # type: ignore
+
+        result = clawlet_rust_core.diff_trees_renames(str(a_root), str(b_root), float(similarity))
+        if isinstance(result, tuple) and len(result) == 6:
+            return (
+                bool(result[0]),
+                [(str(old), str(new)) for old, new in result[1]],
+                [str(rel) for rel in result[2]],
+                [str(rel) for rel in result[3]],
+                [str(rel) for rel in result[4]],
+                str(result[5]),
+            )
+    except Exception:
+        pass
+    return _diff_trees_renames_python(a_root, b_root, similarity)
+
+
+def write_text_file_atomic(
+    path: str,
+    content: str,
+    durable: bool = False,
+) -> tuple[bool, int, str]:
+    """
+    Write UTF-8 text via a sibling temp file and rename, so readers never see a partial file.
+
+    With `durable`, the temp file is fsynced before the rename and the parent directory
+    after it, so the write survives a crash or power loss. That costs one or two disk
+    flushes (often milliseconds each), so reserve it for state that must not be lost.
+    """
+    try:
+        import clawlet_rust_core  # type: ignore
+
+        result = clawlet_rust_core.write_text_file_atomic(str(path), str(content), bool(durable))
+        if isinstance(result, tuple) and len(result) == 3:
+            return (bool(result[0]), int(result[1]), str(result[2]))
+    except Exception:
+        pass
+    return _write_text_file_atomic_python(path, content, durable)
+
+
+def read_bounded(path: str, max_lines: int, max_bytes: int) -> tuple[bool, str, bool, str]:
+    """
+    Read a text file up to `max_lines` lines or `max_bytes` bytes, whichever comes first.
+
+    Returns (ok, content, truncated, error). A cut never splits a UTF-8 character, and
+    `truncated` is set only when the file really has more to show.
+    """
+    try:
+        import clawlet_rust_core  # type: ignore
+
+        result = clawlet_rust_core.read_bounded(str(path), int(max_lines), int(max_bytes))
+        if isinstance(result, tuple) and len(result) == 4:
+            return (bool(result[0]), str(result[1]), bool(result[2]), str(result[3]))
+    except Exception:
+        pass
+    return _read_bounded_python(path, max_lines, max_bytes)
+
+
+def execute_command_hash_only(
+    argv: list[str],
+    cwd: str,
+    timeout_seconds: float,
+) -> tuple[bool, int, str, str]:
+    """
+    Execute command argv and return a digest of its output instead of the output itself.
+
+    stdout and stderr each stream through their own SHA-256 and are discarded; the
+    returned hex digest is SHA-256 over the stdout digest followed by the stderr digest,
+    so it is stable however the two streams interleave.
+
+    Returns:
+      - tuple(success, returncode, output_sha256, error)
+    """
+    try:
+        import clawlet_rust_core  # type: ignore
+
+        result = clawlet_rust_core.execute_command_hash_only(argv, cwd, float(timeout_seconds))
+        if isinstance(result, tuple) and len(result) == 4:
+            return (bool(result[0]), int(result[1]), str(result[2]), str(result[3]))
+    except Exception:
+        pass
+    return _execute_command_hash_only_python(argv, cwd, timeout_seconds)
+
+
+def read_lines_capped(path: str, max_line_bytes: int) -> tuple[bool, list[str], bool, str]:
+    """
+    Read a file as lines (terminators stripped), cutting any line over `max_line_bytes`.
+
+    A cut line keeps its first `max_line_bytes` bytes (never splitting a character) plus
+    a trailing "…", and the rest of it is skipped without being buffered, so a huge
+    minified line costs no more memory than a normal one. The flag reports any cut.
+    """
+    try:
+        import clawlet_rust_core  # type: ignore
+
+        result = clawlet_rust_core.read_lines_capped(str(path), int(max_line_bytes))
+        if isinstance(result, tuple) and len(result) == 4:
+            lines = [str(line) for line in result[1]]
+            return (bool(result[0]), lines, bool(result[2]), str(result[3]))
+    except Exception:
+        pass
+    return _read_lines_capped_python(path, max_line_bytes)
+
+
+def increment_counter_file(path: str) -> tuple[bool, int, str]:
+    """
+    Atomically increment an integer stored as text in `path` and return the new value.
+
+    A missing or empty file counts as 0. The read-modify-write runs under an OS lock on
+    a sibling `<path>.lock` file, so concurrent processes get distinct, increasing values.
+    """
+    try:
+        import clawlet_rust_core  # type: ignore
+
+        result = clawlet_rust_core.increment_counter_file(str(path))
+        if isinstance(result, tuple) and len(result) == 3:
+            return (bool(result[0]), int(result[1]), str(result[2]))
+    except Exception:
+        pass
+    return _increment_counter_file_python(path)
+
+
+def hash_and_size(path: str) -> tuple[bool, str, int, str]:
+    """
+    Return (ok, sha256, size_bytes, error) from one streaming read of the file.
+
+    The size is the number of bytes hashed, so both values always describe the same read.
+    """
+    try:
+        import clawlet_rust_core  # type: ignore
+
+        result = clawlet_rust_core.hash_and_size(str(path))
+        if isinstance(result, tuple) and len(result) == 4:
+            return (bool(result[0]), str(result[1]), int(result[2]), str(result[3]))
+    except Exception:
+        pass
+    return _hash_and_size_python(path)
+
+
+def execute_command_timestamped(
+    argv: list[str],
+    cwd: str,
+    timeout_seconds: float,
+) -> tuple[bool, int, list[tuple[float, str, str]], str]:
+    """
+    Execute command argv, recording when each output chunk arrived.
+
+    Entries are (elapsed_seconds since spawn, stream, text) in arrival order; chunk
+    boundaries are whatever the pipes delivered, not lines.
+
+    Returns:
+      - tuple(success, returncode, entries, error)
+    """
+    try:
+        import clawlet_rust_core  # type: ignore
+
+        result = clawlet_rust_core.execute_command_timestamped(argv, cwd, float(timeout_seconds))
+        if isinstance(result, tuple) and len(result) == 4:
+            entries = [(float(at), str(stream), str(text)) for at, stream, text in result[2]]
+            return (bool(result[0]), int(result[1]), entries, str(result[3]))
+    except Exception:
+        pass
+    return _execute_command_timestamped_python(argv, cwd, timeout_seconds)
+
+
+def collapse_repeats(text: str) -> str:
+    """
+    Fold each run of identical consecutive lines into one line ending "(repeated N times)".
+
+    Single lines are untouched and line terminators are preserved.
+    """
+    try:
+        import clawlet_rust_core  # type: ignore
+
+        result = clawlet_rust_core.collapse_repeats(str(text))
+        if isinstance(result, str):
+            return result
+    except Exception:
+        pass
+    return _collapse_repeats_python(text)
+
+
+def read_dual(path: str) -> tuple[bool, bytes, str, bool, str]:
+    """
+    Read a file once and return both its exact bytes and a lossy UTF-8 text view.
+
+    Returns (ok, data, text, is_valid_utf8, error); when `is_valid_utf8` is False the
+    text has replacement characters where the bytes were not valid UTF-8.
+    """
+    try:
+        import clawlet_rust_core  # type: ignore
+
+        result = clawlet_rust_core.read_dual(str(path))
+        if isinstance(result, tuple) and len(result) == 5:
+            return (
+                bool(result[0]),
+                bytes(result[1]),
+                str(result[2]),
+                bool(result[3]),
+                str(result[4]),
+            )
+    except Exception:
+        pass
+    return _read_dual_python(path)
+
+
+def apply_patch_ignore_ws(original: str, patch: str) -> tuple[bool, str, str]:
+    """
+    Apply a unified diff to text, matching context and removed lines modulo trailing whitespace.
+
+    Only the comparison is relaxed: kept lines come from `original` verbatim and added
+    lines from the patch verbatim.
+    """
+    try:
+        import clawlet_rust_core  # type: ignore
+
+        result = clawlet_rust_core.apply_patch_ignore_ws(str(original), str(patch))
+        if isinstance(result, tuple) and len(result) == 3:
+            return (bool(result[0]), str(result[1]), str(result[2]))
+    except Exception:
+        pass
+    updated, error = _apply_patch_python(original, patch, ignore_trailing_ws=True)
+    if updated is None:
+        return False, "", error
+    return True, updated, ""
+
+
+def hash_tree_progress(
+    root: str,
+    callback: Callable[[int, int], object],
+    respect_gitignore: bool = True,
+) -> tuple[bool, str, str]:
+    """
+    hash_tree with progress: calls callback(files_done, total_files) while hashing.
+
+    Files are listed first so the total is known up front. Calls are throttled to about
+    ten per second, with a final call once every file is hashed. The digest equals
+    hash_tree(root, respect_gitignore). A callback exception aborts and is reported.
+    """
+    try:
+        import clawlet_rust_core  # type: ignore
+
+        rust_progress = clawlet_rust_core.hash_tree_progress
+    except Exception:
+        rust_progress = None
+
+    if rust_progress is not None:
+        try:
+            result = rust_progress(str(root), callback, bool(respect_gitignore))
+        except Exception as e:
+            return False, "", f"Progress callback failed: {e}"
+        if isinstance(result, tuple) and len(result) == 3:
+            return (bool(result[0]), str(result[1]), str(result[2]))
+    return _hash_tree_progress_python(root, callback, respect_gitignore)
+
+
+def compile_regex(pattern: str) -> tuple[bool, int, str]:
+    """
+    Check that `pattern` compiles and return (ok, capture_group_count, error).
+
+    The Rust core uses the regex crate's syntax; the fallback uses Python's `re`, which
+    accepts a few constructs (lookaround, backreferences) the crate rejects.
+    """
+    try:
+        import clawlet_rust_core  # type: ignore
+
+        result = clawlet_rust_core.compile_regex(str(pattern))
+        if isinstance(result, tuple) and len(result) == 3:
+            return (bool(result[0]), int(result[1]), str(result[2]))
+    except Exception:
+        pass
+    return _compile_regex_python(pattern)
+
+
+def byte_histogram(path: str, sample_bytes: int) -> tuple[bool, list[int], str]:
+    """
+    Count each byte value (0-255) in the first `sample_bytes` bytes of a file.
+
+    Only the start of the file is sampled; shorter files are counted whole. The result
+    always has 256 entries.
+    """
+    try:
+        import clawlet_rust_core  # type: ignore
+
+        result = clawlet_rust_core.byte_histogram(str(path), int(sample_bytes))
+        if isinstance(result, tuple) and len(result) == 3:
+            return (bool(result[0]), [int(count) for count in result[1]], str(result[2]))
+    except Exception:
+        pass
+    return _byte_histogram_python(path, sample_bytes)
+
+
+def diff_to_patch_file(
+    old_path: str,
+    new_path: str,
+    out_path: str,
+    context: int = 3,
+) -> tuple[bool, int, str]:
+    """
+    Diff two UTF-8 files and save the unified diff to `out_path`, returning its byte length.
+
+    Headers are labelled a/<old_path> and b/<new_path>. Identical inputs write an empty
+    patch. The output file is replaced atomically.
+    """
+    try:
+        import clawlet_rust_core  # type: ignore
+
+        result = clawlet_rust_core.diff_to_patch_file(
+            str(old_path), str(new_path), str(out_path), int(context)
+        )
+        if isinstance(result, tuple) and len(result) == 3:
+            return (bool(result[0]), int(result[1]), str(result[2]))
+    except Exception:
+        pass
+    return _diff_to_patch_file_python(old_path, new_path, out_path, context)
+
+
+def hash_with(payload: Union[bytes, str], algorithm: str) -> tuple[bool, str, str]:
+    """
+    Hash `payload` with the named algorithm: "sha256", "sha512", "blake3" or "md5".
+
+    Strings are hashed as UTF-8. Without the Rust core, "blake3" needs the optional
+    `blake3` package. fast_hash stays the sha256 shortcut for strings.
+    """
+    data = payload.encode("utf-8") if isinstance(payload, str) else bytes(payload)
+    try:
+        import clawlet_rust_core  # type: ignore
+
+        result = clawlet_rust_core.hash_with(data, str(algorithm))
+        if isinstance(result, tuple) and len(result) == 3:
+            return (bool(result[0]), str(result[1]), str(result[2]))
+    except Exception:
+        pass
+    return _hash_with_python(data, algorithm)
+
+
+def grep_context_numbered(
+    path: str,
+    pattern: str,
+    before: int = 0,
+    after: int = 0,
+) -> tuple[bool, str, str]:
+    """
+    Format regex matches in a file with surrounding lines, like `grep -n -B -A`.
+
+    Matching lines print as "N:text" and context lines as "N-text"; overlapping or
+    adjacent windows merge and separate regions are divided by a "--" line. No match
+    yields an empty string.
+    """
+    try:
+        import clawlet_rust_core  # type: ignore
+
+        result = clawlet_rust_core.grep_context_numbered(
+            str(path), str(pattern), int(before), int(after)
+        )
+        if isinstance(result, tuple) and len(result) == 3:
+            return (bool(result[0]), str(result[1]), str(result[2]))
+    except Exception:
+        pass
+    return _grep_context_numbered_python(path, pattern, before, after)
+
+
+def patch_within_paths(
+    patch: str,
+    allowed_prefixes: list[str],
+) -> tuple[bool, list[str], str]:
+    """
+    Check that every file a patch touches lies under one of `allowed_prefixes`.
+
+    Paths come from the ---/+++ headers with any a/ or b/ prefix removed and are
+    normalized first, so "src/../secrets" does not pass as "src/". Returns (ok,
+    violating_paths, error); ok is False when any path falls outside or the patch has
+    no file headers at all.
+    """
+    try:
+        import clawlet_rust_core  # type: ignore
+
+        result = clawlet_rust_core.patch_within_paths(
+            str(patch), [str(prefix) for prefix in allowed_prefixes]
+        )
+        if isinstance(result, tuple) and len(result) == 3:
+            return (bool(result[0]), [str(path) for path in result[1]], str(result[2]))
+    except Exception:
+        pass
+    return _patch_within_paths_python(patch, allowed_prefixes)
+
+
+def stream_transform(
+    src: str,
+    dst: str,
+    callback: Callable[[str], Optional[str]],
+) -> tuple[bool, int, str]:
+    """
+    Rewrite `src` into `dst` one line at a time through callback(line) -> str | None.
+
+    The callback sees each line without its terminator; its result is written with the
+    original terminator, and None drops the line. Output streams into a temp file next
+    to `dst` that replaces it only once every line succeeded, so memory stays bounded
+    and `dst` is never half-written. A callback exception aborts the transform.
+
+    Returns:
+      - tuple(success, bytes_written, error)
+    """
+    try:
+        import clawlet_rust_core  # type: ignore
+
+        rust_stream = clawlet_rust_core.stream_transform
+    except Exception:
+        rust_stream = None
+
+    if rust_stream is not None:
+        try:
+            result = rust_stream(str(src), str(dst), callback)
+        except Exception as e:
+            return False, 0, f"Transform callback failed: {e}"
+        if isinstance(result, tuple) and len(result) == 3:
+            return (bool(result[0]), int(result[1]), str(result[2]))
+    return _stream_transform_python(src, dst, callback)
+
+
+def timed_read(path: str) -> tuple[bool, str, float, str]:
+    """Read a UTF-8 text file and report how long it took: (ok, content, seconds, error)."""
+    try:
+        import clawlet_rust_core  # type: ignore
+
+        result = clawlet_rust_core.timed_read(str(path))
+        if isinstance(result, tuple) and len(result) == 4:
+            return (bool(result[0]), str(result[1]), float(result[2]), str(result[3]))
+    except Exception:
+        pass
+    return _timed_read_python(path)
+
+
+def timed_write(path: str, content: str) -> tuple[bool, int, float, str]:
+    """Write a UTF-8 text file and report how long it took: (ok, bytes, seconds, error)."""
+    try:
+        import clawlet_rust_core  # type: ignore
+
+        result = clawlet_rust_core.timed_write(str(path), str(content))
+        if isinstance(result, tuple) and len(result) == 4:
+            return (bool(result[0]), int(result[1]), float(result[2]), str(result[3]))
+    except Exception:
+        pass
+    return _timed_write_python(path, content)
+
+
+def split_file_at_line(
+    path: str,
+    line: int,
+    first_out: str,
+    second_out: str,
+) -> tuple[bool, str]:
+    """
+    Write lines 1..`line` of a file to `first_out` and the rest to `second_out`.
+
+    `line` 0 leaves the first file empty and a line past EOF leaves the second empty.
+    Bytes, including line endings, are copied unchanged; both outputs are written
+    atomically.
+    """
+    try:
+        import clawlet_rust_core  # type: ignore
+
+        result = clawlet_rust_core.split_file_at_line(
+            str(path), int(line), str(first_out), str(second_out)
+        )
+        if isinstance(result, tuple) and len(result) == 2:
+            return (bool(result[0]), str(result[1]))
+    except Exception:
+        pass
+    return _split_file_at_line_python(path, line, first_out, second_out)
+
+
+def concat_files(
+    paths: list[str],
+    out_path: str,
+    separator: str = "",
+    skip_missing: bool = False,
+) -> tuple[bool, int, str]:
+    """
+    Concatenate files in order into `out_path`, with `separator` between each pair.
+
+    Inputs are streamed into a temp file beside `out_path` that replaces it at the end.
+    A missing input is an error unless `skip_missing`, in which case it is left out
+    (and gets no separator). Returns the total bytes written.
+    """
+    try:
+        import clawlet_rust_core  # type: ignore
+
+        result = clawlet_rust_core.concat_files(
+            [str(path) for path in paths], str(out_path), str(separator), bool(skip_missing)
+        )
+        if isinstance(result, tuple) and len(result) == 3:
+            return (bool(result[0]), int(result[1]), str(result[2]))
+    except Exception:
+        pass
+    return _concat_files_python(paths, out_path, separator, skip_missing)
+
+
+def detect_output_encoding(
+    argv: list[str],
+    cwd: str,
+    timeout_seconds: float,
+) -> tuple[bool, str, str]:
+    """
+    Run argv and guess the character encoding of its combined output.
+
+    Returns (ok, encoding_name, error) with WHATWG names such as "UTF-8", "UTF-16LE" or
+    "windows-1252", so a follow-up run can decode correctly. A nonzero exit still yields
+    a guess; spawn failures and timeouts do not. The Rust core sniffs statistically;
+    the fallback only recognises UTF-8, UTF-16 and the locale's preferred encoding,
+    defaulting to windows-1252.
+    """
+    try:
+        import clawlet_rust_core  # type: ignore
+
+        result = clawlet_rust_core.detect_output_encoding(argv, cwd, float(timeout_seconds))
+        if isinstance(result, tuple) and len(result) == 3:
+            return (bool(result[0]), str(result[1]), str(result[2]))
+    except Exception:
+        pass
+    return _detect_output_encoding_python(argv, cwd, timeout_seconds)
+
+
+def read_byte_range(path: str, start: int, end: int) -> tuple[bool, str, str]:
+    """
+    Read bytes [start, end) of a file and decode them as UTF-8 text.
+
+    A range that begins or ends inside a multibyte character drops those partial bytes
+    instead of producing replacement characters; ranges past EOF are clamped.
+    """
+    try:
+        import clawlet_rust_core  # type: ignore
+
+        result = clawlet_rust_core.read_byte_range(str(path), int(start), int(end))
+        if isinstance(result, tuple) and len(result) == 3:
+            return (bool(result[0]), str(result[1]), str(result[2]))
+    except Exception:
+        pass
+    return _read_byte_range_python(path, start, end)
+
+
+def execute_with_retries(
+    argv: list[str],
+    cwd: str,
+    timeout_seconds: float,
+    max_attempts: int,
+    backoff_seconds: float,
+    jitter_fraction: float = 0.0,
+    seed: Optional[int] = None,
+) -> tuple[bool, int, str, str, str, list[tuple[float, int]]]:
+    """
+    Execute command argv until it succeeds or `max_attempts` runs have failed.
+
+    The wait before retry n (1-based) is `backoff_seconds * 2 ** (n - 1)`, scaled by a
+    random factor in [1 - jitter_fraction, 1 + jitter_fraction] so many agents retrying
+    at once spread out. Pass `seed` for a reproducible jitter sequence.
+
+    Returns:
+      - tuple(success, returncode, stdout, stderr, error, attempts) where stdout/stderr/
+        error come from the last run and attempts lists (duration_seconds, returncode)
+        per run
+    """
+    try:
+        import clawlet_rust_core  # type: ignore
+
+        result = clawlet_rust_core.execute_with_retries(
+            argv,
+            cwd,
+            float(timeout_seconds),
+            int(max_attempts),
+            float(backoff_seconds),
+            float(jitter_fraction),
+            seed,
+        )
+        if isinstance(result, tuple) and len(result) == 6:
+            attempts = [(float(duration), int(code)) for duration, code in result[5]]
+            return (
+                bool(result[0]),
+                int(result[1]),
+                str(result[2]),
+                str(result[3]),
+                str(result[4]),
+                attempts,
+            )
+    except Exception:
+        pass
+    return _execute_with_retries_python(
+        argv, cwd, timeout_seconds, max_attempts, backoff_seconds, jitter_fraction, seed
+    )
+
+
+def patch_roundtrips(original: str, patch: str) -> tuple[bool, bool, str]:
+    """
+    Apply a patch, apply its reverse to the result, and check the original comes back.
+
+    Returns (ok, roundtrips, error); ok is False only when the forward or reverse step
+    cannot be performed at all.
+    """
+    try:
+        import clawlet_rust_core  # type: ignore
+
+        result = clawlet_rust_core.patch_roundtrips(str(original), str(patch))
+        if isinstance(result, tuple) and len(result) == 3:
+            return (bool(result[0]), bool(result[1]), str(result[2]))
+    except Exception:
+        pass
+    return _patch_roundtrips_python(original, patch)
+
+
+def list_by_mtime(
+    root: str,
+    limit: int,
+    respect_gitignore: bool = True,
+) -> tuple[bool, list[tuple[str, float]], str]:
+    """
+    Return the `limit` most recently modified files under root as (relpath, mtime), newest first.
+
+    Only a bounded top-N is kept while walking, so large trees are never fully sorted.
+    """
+    try:
+        import clawlet_rust_core  # type: ignore
+
+        result = clawlet_rust_core.list_by_mtime(str(root), int(limit), bool(respect_gitignore))
+        if isinstance(result, tuple) and len(result) == 3:
+            files = [(str(rel), float(mtime)) for rel, mtime in result[1]]
+            return (bool(result[0]), files, str(result[2]))
+    except Exception:
+        pass
+    return _list_by_mtime_python(root, limit, respect_gitignore)
+
+
+def repo_relative_path(path: str) -> tuple[bool, str, str, str]:
+    """
+    Locate the git repository enclosing `path` and express `path` relative to its root.
+
+    Returns (ok, repo_root, relpath, error) with a POSIX-style relpath ("." for the root
+    itself), so stored references stay valid wherever the repo is checked out. Symlinks
+    are resolved first; a `.git` file (worktrees, submodules) counts as a repo marker.
+    """
+    try:
+        import clawlet_rust_core  # type: ignore
+
+        result = clawlet_rust_core.repo_relative_path(str(path))
+        if isinstance(result, tuple) and len(result) == 4:
+            return (bool(result[0]), str(result[1]), str(result[2]), str(result[3]))
+    except Exception:
+        pass
+    return _repo_relative_path_python(path)
+
+
+def execute_json(
+    argv: list[str],
+    cwd: str,
+    timeout_seconds: float,
+) -> tuple[bool, int, str, bool, str]:
+    """
+    Execute command argv and report whether its stdout is a single valid JSON document.
+
+    stdout is returned raw so callers can parse it into their own types; surrounding
+    whitespace is allowed, while empty output and the NaN/Infinity extensions are not JSON.
+
+    Returns:
+      - tuple(success, returncode, stdout, stdout_is_valid_json, error)
+    """
+    try:
+        import clawlet_rust_core  # type: ignore
+
+        result = clawlet_rust_core.execute_json(argv, cwd, float(timeout_seconds))
+        if isinstance(result, tuple) and len(result) == 5:
+            return (
+                bool(result[0]),
+                int(result[1]),
+                str(result[2]),
+                bool(result[3]),
+                str(result[4]),
+            )
+    except Exception:
+        pass
+    return _execute_json_python(argv, cwd, timeout_seconds)
+
+
+def rename_aware_fingerprint(patch: str) -> tuple[bool, str, str]:
+    """
+    Compute a sha256 cache key for a patch that ignores how the diff was rendered.
+
+    The key covers, per file and sorted by path: the operation (create, delete, modify,
+    or rename old -> new, taken from "rename from/to", the ---/+++ headers or the
+    "diff --git" line) and each run of removed and added lines anchored at its old-side
+    line number. Context lines, hunk boundaries, "diff --git", "index", "similarity
+    index" and "old mode/new mode" lines are left out, so a git rename diff and its
+    minimized form produce the same digest; a mode-only change is not part of the key.
+
+    Returns:
+      - tuple(ok, hex_digest, error)
+    """
+    try:
+        import clawlet_rust_core  # type: ignore
+
+        result = clawlet_rust_core.rename_aware_fingerprint(str(patch))
+        if isinstance(result, tuple) and len(result) == 3:
+            return (bool(result[0]), str(result[1]), str(result[2]))
+    except Exception:
+        pass
+    return _rename_aware_fingerprint_python(patch)
+
+
+def glob_match_many(
+    root: str,
+    includes: list[str],
+    excludes: list[str],
+    respect_gitignore: bool = False,
+) -> tuple[bool, list[str], str]:
+    """
+    List files under root matching any include glob and no exclude glob, in one walk.
+
+    Globs use the same root-relative posix syntax as count_glob and each list is
+    compiled into a single combined matcher. Returns (ok, sorted_relpaths, error).
+    """
+    try:
+        import clawlet_rust_core  # type: ignore
+
+        result = clawlet_rust_core.glob_match_many(
+            str(root),
+            [str(pattern) for pattern in includes],
+            [str(pattern) for pattern in excludes],
+            bool(respect_gitignore),
+        )
+        if isinstance(result, tuple) and len(result) == 3:
+            return (bool(result[0]), [str(path) for path in result[1]], str(result[2]))
+    except Exception:
+        pass
+    return _glob_match_many_python(root, includes, excludes, respect_gitignore)
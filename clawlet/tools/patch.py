@@ -13,6 +13,9 @@ from clawlet.runtime.rust_bridge import validate_patch
 from clawlet.tools.files import _secure_resolve
 from clawlet.tools.registry import BaseTool, ToolResult
 
+_NUMBERED_HUNK_RE = re.compile(r"^@@ -(\d+)(?:,\d+)? \+(\d+)(?:,\d+)? @@")
+_UNNUMBERED_HUNK_RE = re.compile(r"^@@(?: .*)?$")
+
 
 class ApplyPatchTool(BaseTool):
     """Apply a unified diff patch to a single file."""
@@ -36,14 +39,27 @@ class ApplyPatchTool(BaseTool):
             "properties": {
                 "path": {"type": "string", "description": "Target file path"},
                 "patch": {"type": "string", "description": "Unified diff text"},
+                "search_context": {
+                    "type": "boolean",
+                    "description": "If a hunk doesn't match at its stated line, apply it at the "
+                    "unique place its context appears in the file",
+                    "default": False,
+                },
             },
             "required": ["path", "patch"],
         }
 
-    async def execute(self, path: str, patch: str, **kwargs) -> ToolResult:
+    async def execute(
+        self,
+        path: str,
+        patch: str,
+        search_context: bool = False,
+        **kwargs,
+    ) -> ToolResult:
         try:
             ok, reason = validate_patch(patch)
-            if not ok:
+            # Hunks without line numbers fail strict validation but are fine to relocate.
+            if not ok and not (search_context and self._has_unnumbered_hunks(patch)):
                 return ToolResult(success=False, output="", error=reason)
 
             target = Path(path)
@@ -55,7 +71,9 @@ class ApplyPatchTool(BaseTool):
             original_text = resolved_path.read_text(encoding="utf-8")
             if self.use_rust_core:
                 rust_result = rust_apply_unified_patch(original_text, patch)
-                if rust_result is not None:
+                # The Rust core applies strictly; with search_context a positional miss
+                # falls through to the Python applier's context search.
+                if rust_result is not None and (rust_result[0] or not search_context):
                     ok, updated_text, error = rust_result
                     if not ok:
                         return ToolResult(success=False, output="", error=error or "Patch apply error")
@@ -69,7 +87,11 @@ class ApplyPatchTool(BaseTool):
                     )
 
             original_lines = original_text.splitlines(keepends=True)
-            new_lines = self._apply_unified_diff(original_lines, patch)
+            new_lines = self._apply_unified_diff(
+                original_lines,
+                patch,
+                search_context=search_context,
+            )
             resolved_path.write_text("".join(new_lines), encoding="utf-8")
             return ToolResult(
                 success=True,
@@ -79,57 +101,114 @@ class ApplyPatchTool(BaseTool):
         except Exception as e:
             return ToolResult(success=False, output="", error=str(e))
 
-    def _apply_unified_diff(self, src_lines: list[str], diff_text: str) -> list[str]:
+    def _apply_unified_diff(
+        self,
+        src_lines: list[str],
+        diff_text: str,
+        search_context: bool = False,
+    ) -> list[str]:
         """
         Minimal unified-diff applier supporting @@ hunks for single-file edits.
+
+        With search_context, a hunk whose old-side lines don't match at the stated
+        position is applied at the unique occurrence of those lines in the file, and
+        hunk headers without line numbers (`@@ @@`) are accepted and always searched.
         """
-        lines = diff_text.splitlines()
-        hunk_re = re.compile(r"^@@ -(\d+)(?:,\d+)? \+(\d+)(?:,\d+)? @@")
+        hunks = self._parse_hunks(diff_text, search_context=search_context)
+        if not hunks and diff_text.strip():
+            raise ValueError("Patch contains no applicable hunks")
         out: list[str] = []
         src_idx = 0
-        i = 0
-
-        while i < len(lines):
-            m = hunk_re.match(lines[i])
-            if not m:
-                i += 1
-                continue
 
-            old_start = int(m.group(1)) - 1
-            out.extend(src_lines[src_idx:old_start])
-            src_idx = old_start
-            i += 1
-
-            while i < len(lines) and not lines[i].startswith("@@"):
-                line = lines[i]
-                if line.startswith("--- ") or line.startswith("+++ "):
-                    i += 1
-                    continue
-                if not line:
-                    token = " "
-                    body = ""
-                else:
-                    token = line[0]
-                    body = line[1:]
+        for old_start, body in hunks:
+            start = max(old_start - 1, 0) if old_start is not None else src_idx
+            if search_context:
+                old_block = [text for token, text in body if token in (" ", "-")]
+                if old_start is None or not self._block_matches(src_lines, start, old_block):
+                    start = self._find_unique_block(src_lines, src_idx, old_block)
+            out.extend(src_lines[src_idx:start])
+            src_idx = start
 
+            for token, text in body:
                 if token == " ":
-                    expected = src_lines[src_idx].rstrip("\n")
-                    if expected != body:
+                    if src_idx >= len(src_lines) or src_lines[src_idx].rstrip("\n") != text:
                         raise ValueError("Patch context mismatch")
                     out.append(src_lines[src_idx])
                     src_idx += 1
                 elif token == "-":
-                    expected = src_lines[src_idx].rstrip("\n")
-                    if expected != body:
+                    if src_idx >= len(src_lines) or src_lines[src_idx].rstrip("\n") != text:
                         raise ValueError("Patch removal mismatch")
                     src_idx += 1
                 elif token == "+":
-                    out.append(body + "\n")
-                elif token == "\\":
-                    pass
-                else:
-                    raise ValueError(f"Unsupported diff token: {token}")
-                i += 1
+                    out.append(text + "\n")
 
         out.extend(src_lines[src_idx:])
         return out
+
+    def _parse_hunks(
+        self,
+        diff_text: str,
+        search_context: bool = False,
+    ) -> list[tuple[Optional[int], list[tuple[str, str]]]]:
+        """
+        Split a diff into (old_start, [(token, text), ...]) hunks.
+
+        old_start is None for a header without line numbers, which is only accepted
+        with search_context. Any other `@@` line ends the current hunk, and lines up
+        to the next valid header are ignored.
+        """
+        hunks: list[tuple[Optional[int], list[tuple[str, str]]]] = []
+        body: Optional[list[tuple[str, str]]] = None
+
+        for line in diff_text.splitlines():
+            m = _NUMBERED_HUNK_RE.match(line)
+            if m:
+                body = []
+                hunks.append((int(m.group(1)), body))
+                continue
+            if search_context and _UNNUMBERED_HUNK_RE.match(line):
+                body = []
+                hunks.append((None, body))
+                continue
+            if line.startswith("@@"):
+                body = None
+                continue
+            if body is None or line.startswith("--- ") or line.startswith("+++ "):
+                continue
+            token, text = (line[0], line[1:]) if line else (" ", "")
+            if token == "\\":
+                continue
+            if token not in (" ", "-", "+"):
+                raise ValueError(f"Unsupported diff token: {token}")
+            body.append((token, text))
+        return hunks
+
+    def _block_matches(self, src_lines: list[str], start: int, block: list[str]) -> bool:
+        if start + len(block) > len(src_lines):
+            return False
+        return all(src_lines[start + i].rstrip("\n") == text for i, text in enumerate(block))
+
+    def _find_unique_block(self, src_lines: list[str], from_idx: int, block: list[str]) -> int:
+        if not block:
+            raise ValueError("Cannot relocate a hunk without context or removed lines")
+        matches = [
+            start
+            for start in range(len(src_lines) - len(block) + 1)
+            if self._block_matches(src_lines, start, block)
+        ]
+        if not matches:
+            raise ValueError("Patch context not found in file")
+        if len(matches) > 1:
+            raise ValueError(f"Patch context is ambiguous: found {len(matches)} occurrences")
+        if matches[0] < from_idx:
+            raise ValueError(
+                f"Patch context found at line {matches[0] + 1}, which overlaps or precedes "
+                "an earlier hunk; hunks must be in file order"
+            )
+        return matches[0]
+
+    def _has_unnumbered_hunks(self, diff_text: str) -> bool:
+        return any(
+            _UNNUMBERED_HUNK_RE.match(line) and not _NUMBERED_HUNK_RE.match(line)
+            for line in diff_text.splitlines()
+        )
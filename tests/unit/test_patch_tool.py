@@ -0,0 +1,74 @@
+from __future__ import annotations
+
+from pathlib import Path
+
+import pytest
+
+from clawlet.tools.patch import ApplyPatchTool
+
+DRIFTED_PATCH = "@@ -1,3 +1,3 @@\n alpha\n-beta\n+BETA\n gamma\n"
+
+
+@pytest.mark.unit
+@pytest.mark.asyncio
+async def test_apply_patch_search_context_relocates_drifted_hunk(tmp_path: Path):
+    target = tmp_path / "notes.txt"
+    target.write_text("header\nintro\nalpha\nbeta\ngamma\n", encoding="utf-8")
+    tool = ApplyPatchTool(allowed_dir=tmp_path, use_rust_core=False)
+
+    strict = await tool.execute(path=str(target), patch=DRIFTED_PATCH)
+    assert strict.success is False
+
+    result = await tool.execute(path=str(target), patch=DRIFTED_PATCH, search_context=True)
+    assert result.success is True
+    assert target.read_text(encoding="utf-8") == "header\nintro\nalpha\nBETA\ngamma\n"
+
+
+@pytest.mark.unit
+@pytest.mark.asyncio
+async def test_apply_patch_search_context_rejects_ambiguous_context(tmp_path: Path):
+    target = tmp_path / "notes.txt"
+    target.write_text("x\nalpha\nbeta\ngamma\nalpha\nbeta\ngamma\n", encoding="utf-8")
+    tool = ApplyPatchTool(allowed_dir=tmp_path, use_rust_core=False)
+
+    result = await tool.execute(path=str(target), patch=DRIFTED_PATCH, search_context=True)
+
+    assert result.success is False
+    assert "ambiguous" in (result.error or "")
+
+
+@pytest.mark.unit
+@pytest.mark.asyncio
+async def test_apply_patch_search_context_accepts_hunks_without_line_numbers(tmp_path: Path):
+    target = tmp_path / "notes.txt"
+    target.write_text("a\nb\nc\n", encoding="utf-8")
+    tool = ApplyPatchTool(allowed_dir=tmp_path, use_rust_core=False)
+
+    strict = await tool.execute(path=str(target), patch="@@ @@\n a\n-b\n+B\n")
+    assert strict.success is False
+
+    result = await tool.execute(path=str(target), patch="@@ @@\n a\n-b\n+B\n", search_context=True)
+    assert result.success is True
+    assert target.read_text(encoding="utf-8") == "a\nB\nc\n"
+
+
+@pytest.mark.unit
+def test_apply_unified_diff_search_context_checks_whole_file_and_hunk_order():
+    tool = ApplyPatchTool(use_rust_core=False)
+    src = ["x\n", "y\n", "z\n", "x\n", "y\n"]
+
+    ambiguous = "@@ -3,1 +3,1 @@\n-z\n+Z\n@@ -9,2 +9,2 @@\n x\n-y\n+Y\n"
+    with pytest.raises(ValueError, match="ambiguous"):
+        tool._apply_unified_diff(src, ambiguous, True)
+    with pytest.raises(ValueError, match="earlier hunk"):
+        tool._apply_unified_diff(src, "@@ -5,1 +5,1 @@\n-y\n+Y\n@@ -9,1 +9,1 @@\n-z\n+Z\n", True)
+    with pytest.raises(ValueError, match="no applicable hunks"):
+        tool._apply_unified_diff(src, "@@@ -1 +1 @@@\n-x\n+X\n")
+
+
+@pytest.mark.unit
+def test_apply_unified_diff_malformed_header_ends_hunk():
+    tool = ApplyPatchTool(use_rust_core=False)
+
+    patch = "@@ -1,1 +1,1 @@\n-a\n+A\n@@@ note\nignored line\n"
+    assert tool._apply_unified_diff(["a\n", "b\n"], patch) == ["A\n", "b\n"]
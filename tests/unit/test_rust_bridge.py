@@ -0,0 +1,1621 @@
+from __future__ import annotations
+
+import hashlib
+import json
+import os
+import queue
+import sys
+import time
+from pathlib import Path
+
+import pytest
+
+from clawlet.runtime import bridge_fallbacks, rust_bridge
+
+
+@pytest.mark.unit
+def test_locate_hunk_finds_drifted_position_and_reports_fuzz():
+    original = "".join(f"line {i}\n" for i in range(1, 41))
+
+    exact = ["line 20", "line 21", "line 22"]
+    ok, offset, cost, error = rust_bridge.locate_hunk(original, exact, stated_line=15)
+    assert (ok, offset, cost, error) == (True, 19, 0, "")
+
+    fuzzy = ["line 20", "changed", "line 22"]
+    ok, offset, cost, _ = rust_bridge.locate_hunk(original, fuzzy, stated_line=18)
+    assert (ok, offset, cost) == (True, 19, 1)
+
+    ok, offset, _, _ = rust_bridge.locate_hunk(original, ["nope", "nada", "zilch"], stated_line=10)
+    assert ok is True and offset is None
+
+
+@pytest.mark.unit
+@pytest.mark.skipif(os.name == "nt", reason="Unix mode bits")
+def test_set_permissions_round_trips_mode(tmp_path: Path):
+    script = tmp_path / "run.sh"
+    script.write_text("#!/bin/sh\n", encoding="utf-8")
+
+    assert rust_bridge.set_permissions(str(script), 0o755) == (True, "")
+    assert rust_bridge.get_permissions(str(script)) == (True, 0o755, False, "")
+
+    ok, error = rust_bridge.set_permissions(str(tmp_path / "missing"), 0o644)
+    assert ok is False and "not found" in error
+
+
+@pytest.mark.unit
+def test_execute_command_argv_bounded_keeps_tail_and_counts_total(tmp_path: Path):
+    argv = [sys.executable, "-c", "import sys; sys.stdout.write('x' * 5000 + 'END')"]
+
+    success, code, stdout, stderr, error, stdout_total, stderr_total = (
+        rust_bridge.execute_command_argv_bounded(argv, str(tmp_path), 10.0, 10)
+    )
+
+    assert (success, code, error) == (True, 0, "")
+    assert stdout == "xxxxxxxEND"
+    assert (stdout_total, stderr_total, stderr) == (5003, 0, "")
+
+
+@pytest.mark.unit
+@pytest.mark.skipif(os.name == "nt", reason="process groups are POSIX-only")
+def test_execute_command_argv_bounded_timeout_kills_grandchildren(tmp_path: Path):
+    started = time.monotonic()
+    success, code, _, _, error, _, _ = rust_bridge.execute_command_argv_bounded(
+        ["sh", "-c", "sleep 6; echo late"], str(tmp_path), 1.0, 100
+    )
+
+    assert (success, code) == (False, -1)
+    assert "timed out" in error
+    assert time.monotonic() - started < 4.0
+
+
+@pytest.mark.unit
+def test_read_line_ranges_serves_each_range_independently(tmp_path: Path):
+    target = tmp_path / "notes.txt"
+    target.write_text("a\nb\nc\nd\ne\n", encoding="utf-8")
+
+    ok, groups, error = rust_bridge.read_line_ranges(str(target), [(4, 9), (1, 2), (2, 3)])
+    assert (ok, error) == (True, "")
+    assert groups == [["d", "e"], ["a", "b"], ["b", "c"]]
+
+    assert rust_bridge.read_line_ranges(str(target), [(3, 2)])[0] is False
+
+
+@pytest.mark.unit
+def test_hash_tree_ignores_extensions_and_gitignored_files(tmp_path: Path):
+    (tmp_path / ".gitignore").write_text("build/\n", encoding="utf-8")
+    (tmp_path / "main.py").write_text("print(1)\n", encoding="utf-8")
+    (tmp_path / "build").mkdir()
+    (tmp_path / "build" / "out.bin").write_text("artifact", encoding="utf-8")
+
+    ok, before, error = rust_bridge.hash_tree(str(tmp_path), ignore_extensions=[".log"])
+    assert (ok, error) == (True, "")
+
+    (tmp_path / "run.log").write_text("noise", encoding="utf-8")
+    (tmp_path / "build" / "out.bin").write_text("changed", encoding="utf-8")
+    assert rust_bridge.hash_tree(str(tmp_path), ignore_extensions=["log"])[1] == before
+    assert rust_bridge.hash_tree(str(tmp_path))[1] != before
+
+
+@pytest.mark.unit
+def test_transform_file_writes_result_and_leaves_file_on_callback_error(tmp_path: Path):
+    target = tmp_path / "config.txt"
+    target.write_text("debug = false\n", encoding="utf-8")
+
+    def enable(text: str) -> str:
+        return text.replace("false", "true")
+
+    ok, written, error = rust_bridge.transform_file(str(target), enable)
+    assert (ok, written, error) == (True, 13, "")
+    assert target.read_text(encoding="utf-8") == "debug = true\n"
+
+    def explode(_text: str) -> str:
+        raise RuntimeError("boom")
+
+    ok, _, error = rust_bridge.transform_file(str(target), explode)
+    assert ok is False and "boom" in error
+    assert target.read_text(encoding="utf-8") == "debug = true\n"
+    assert sorted(p.name for p in tmp_path.iterdir()) == ["config.txt"]
+
+
+@pytest.mark.unit
+def test_debug_open_handles_returns_to_baseline_after_commands(tmp_path: Path):
+    baseline = rust_bridge.debug_open_handles()
+
+    for argv in ([sys.executable, "-c", "pass"], ["definitely-not-a-command"]):
+        rust_bridge.execute_command_argv_bounded(argv, str(tmp_path), 10.0, 64)
+
+    assert rust_bridge.debug_open_handles() == baseline
+
+
+@pytest.mark.unit
+def test_remap_line_follows_insertions_and_deletions():
+    patch = "@@ -2,3 +2,4 @@\n b\n-c\n+C1\n+C2\n d\n@@ -8,0 +10,1 @@\n+tail\n"
+
+    assert rust_bridge.remap_line(patch, 1) == (True, 1, "")
+    assert rust_bridge.remap_line(patch, 2) == (True, 2, "")
+    assert rust_bridge.remap_line(patch, 3) == (True, None, "")
+    assert rust_bridge.remap_line(patch, 4) == (True, 5, "")
+    assert rust_bridge.remap_line(patch, 8) == (True, 9, "")
+    assert rust_bridge.remap_line(patch, 9) == (True, 11, "")
+
+
+@pytest.mark.unit
+def test_chunk_text_overlaps_windows_and_keeps_short_tail():
+    content = "".join(f"{i}\n" for i in range(1, 8))
+
+    chunks = rust_bridge.chunk_text(content, 3, 1)
+
+    assert [(start, end) for start, end, _ in chunks] == [(1, 3), (3, 5), (5, 7)]
+    assert chunks[1][2] == "3\n4\n5\n"
+    assert rust_bridge.chunk_text("", 3, 1) == []
+    with pytest.raises(ValueError):
+        rust_bridge.chunk_text(content, 2, 2)
+
+
+@pytest.mark.unit
+def test_reverse_patch_swaps_sides_and_reports_reversible():
+    patch = "--- a/app.py\n+++ b/app.py\n@@ -1,2 +1,2 @@\n keep\n-old\n+new\n"
+
+    ok, reversed_text, error = rust_bridge.reverse_patch(patch)
+
+    assert (ok, error) == (True, "")
+    assert reversed_text == "--- b/app.py\n+++ a/app.py\n@@ -1,2 +1,2 @@\n keep\n+old\n-new\n"
+    assert rust_bridge.is_patch_reversible(patch) == (True, True, "ok")
+    assert rust_bridge.is_patch_reversible("not a patch")[0] is False
+
+
+@pytest.mark.unit
+def test_hash_parts_matches_joined_digest_and_is_order_sensitive():
+    expected = hashlib.sha256("model|v2|prompt".encode("utf-8")).hexdigest()
+
+    assert rust_bridge.hash_parts(["model", "v2", "prompt"], "|") == expected
+    assert rust_bridge.hash_parts(["v2", "model", "prompt"], "|") != expected
+
+
+@pytest.mark.unit
+def test_read_text_strict_rejects_invalid_utf8(tmp_path: Path):
+    good = tmp_path / "good.txt"
+    good.write_text("héllo", encoding="utf-8")
+    bad = tmp_path / "bad.bin"
+    bad.write_bytes(b"ok\xffno")
+
+    assert rust_bridge.read_text_strict(str(good)) == (True, "héllo", "")
+    ok, content, error = rust_bridge.read_text_strict(str(bad))
+    assert (ok, content) == (False, "")
+    assert "offset 2" in error
+
+
+@pytest.mark.unit
+def test_build_manifest_lists_sorted_files_with_size_and_digest(tmp_path: Path):
+    (tmp_path / "src").mkdir()
+    (tmp_path / "src" / "b.py").write_text("b", encoding="utf-8")
+    (tmp_path / "a.txt").write_text("aaa", encoding="utf-8")
+
+    ok, entries, error = rust_bridge.build_manifest(str(tmp_path))
+
+    assert (ok, error) == (True, "")
+    assert entries == [
+        ("a.txt", 3, hashlib.sha256(b"aaa").hexdigest()),
+        ("src/b.py", 1, hashlib.sha256(b"b").hexdigest()),
+    ]
+
+
+@pytest.mark.unit
+def test_execute_command_streaming_line_mode_emits_whole_lines(tmp_path: Path):
+    script = (
+        "import sys; sys.stdout.write('one\\ntw'); sys.stdout.flush(); "
+        "sys.stdout.write('o\\nlast')"
+    )
+    seen: list[tuple[str, str]] = []
+
+    success, code, error = rust_bridge.execute_command_streaming(
+        [sys.executable, "-c", script],
+        str(tmp_path),
+        10.0,
+        lambda stream, text: seen.append((stream, text)),
+        line_mode=True,
+    )
+
+    assert (success, code, error) == (True, 0, "")
+    assert seen == [("stdout", "one"), ("stdout", "two"), ("stdout", "last")]
+
+
+@pytest.mark.unit
+def test_execute_command_streaming_line_mode_splits_overlong_lines(tmp_path: Path):
+    seen: list[str] = []
+
+    rust_bridge.execute_command_streaming(
+        [sys.executable, "-c", "print('x' * 10)"],
+        str(tmp_path),
+        10.0,
+        lambda _stream, text: seen.append(text),
+        line_mode=True,
+        max_line_bytes=4,
+    )
+
+    assert seen == ["xxxx", "xxxx", "xx"]
+
+
+@pytest.mark.unit
+def test_minimize_patch_drops_git_metadata():
+    git_patch = (
+        "diff --git a/app.py b/app.py\n"
+        "index 83db48f..bf269f4 100644\n"
+        "--- a/app.py\n"
+        "+++ b/app.py\n"
+        "@@ -1,2 +1,2 @@\n"
+        " keep\n"
+        "-old\n"
+        "+new\n"
+    )
+
+    ok, minimized, error = rust_bridge.minimize_patch(git_patch)
+
+    assert (ok, error) == (True, "")
+    assert minimized == "--- a/app.py\n+++ b/app.py\n@@ -1,2 +1,2 @@\n keep\n-old\n+new\n"
+
+
+@pytest.mark.unit
+def test_line_hashes_strips_lf_and_crlf_terminators(tmp_path: Path):
+    target = tmp_path / "mixed.txt"
+    target.write_bytes(b"alpha\r\nbeta\n\r\ngamma")
+
+    ok, digests, error = rust_bridge.line_hashes(str(target))
+
+    assert (ok, error) == (True, "")
+    expected = [hashlib.sha256(line).hexdigest() for line in (b"alpha", b"beta", b"", b"gamma")]
+    assert digests == expected
+    assert rust_bridge.line_hashes(str(tmp_path / "missing.txt"))[0] is False
+
+
+@pytest.mark.unit
+def test_execute_command_min_runtime_flags_fast_exit(tmp_path: Path):
+    result = rust_bridge.execute_command_min_runtime(
+        [sys.executable, "-c", "print('hi')"], str(tmp_path), 10.0, 60.0
+    )
+
+    success, code, stdout, _, error, elapsed, too_fast = result
+    assert (success, code, stdout.strip(), error) == (True, 0, "hi", "")
+    assert 0.0 < elapsed < 60.0
+    assert too_fast is True
+
+
+@pytest.mark.unit
+def test_diff_trees_reports_added_removed_and_modified(tmp_path: Path):
+    a_root, b_root = tmp_path / "a", tmp_path / "b"
+    for root in (a_root, b_root):
+        root.mkdir()
+        (root / "same.txt").write_text("same", encoding="utf-8")
+    (a_root / "gone.txt").write_text("x", encoding="utf-8")
+    (a_root / "edit.txt").write_text("v1", encoding="utf-8")
+    (b_root / "edit.txt").write_text("v2", encoding="utf-8")
+    (b_root / "new.txt").write_text("y", encoding="utf-8")
+
+    result = rust_bridge.diff_trees(str(a_root), str(b_root))
+
+    assert result == (True, ["new.txt"], ["gone.txt"], ["edit.txt"], "")
+
+
+@pytest.mark.unit
+def test_base64_round_trips_binary_and_rejects_garbage(tmp_path: Path):
+    source = tmp_path / "blob.bin"
+    source.write_bytes(bytes(range(256)))
+
+    ok, encoded, error = rust_bridge.read_base64(str(source))
+    assert (ok, error) == (True, "")
+
+    copy = tmp_path / "copy.bin"
+    assert rust_bridge.write_base64(str(copy), encoded) == (True, 256, "")
+    assert copy.read_bytes() == source.read_bytes()
+
+    ok, _, error = rust_bridge.write_base64(str(tmp_path / "bad.bin"), "not base64!")
+    assert ok is False and "Invalid base64" in error
+    assert not (tmp_path / "bad.bin").exists()
+
+
+@pytest.mark.unit
+def test_count_glob_counts_recursive_and_top_level_matches(tmp_path: Path):
+    (tmp_path / "pkg" / "sub").mkdir(parents=True)
+    for rel in ("top.py", "pkg/a.py", "pkg/sub/b.py", "pkg/readme.md"):
+        (tmp_path / rel).write_text("", encoding="utf-8")
+
+    assert rust_bridge.count_glob(str(tmp_path), "**/*.py") == (True, 3, "")
+    assert rust_bridge.count_glob(str(tmp_path), "*.py") == (True, 1, "")
+    assert rust_bridge.count_glob(str(tmp_path), "pkg/*") == (True, 3, "")
+
+
+@pytest.mark.unit
+def test_format_patch_recomputes_headers_and_rewindows_context():
+    patch = (
+        "--- a/f.txt  \n"
+        "+++ b/f.txt\n"
+        "@@ -1,7 +1,7 @@   def main():\n"
+        " a\n"
+        "-b\n"
+        "+B\n"
+        " c\n"
+        " d\n"
+        " e\n"
+        "-f\n"
+        "+F\n"
+        " g\n"
+    )
+
+    ok, formatted, error = rust_bridge.format_patch(patch)
+    assert (ok, error) == (True, "")
+    assert formatted.splitlines()[:3] == [
+        "--- a/f.txt",
+        "+++ b/f.txt",
+        "@@ -1,7 +1,7 @@ def main():",
+    ]
+
+    ok, narrowed, _ = rust_bridge.format_patch(patch, context=0)
+    assert ok is True
+    assert [line for line in narrowed.splitlines() if line.startswith("@@")] == [
+        "@@ -2,1 +2,1 @@ def main():",
+        "@@ -6,1 +6,1 @@",
+    ]
+    assert rust_bridge.format_patch("garbage")[0] is False
+
+
+@pytest.mark.unit
+@pytest.mark.skipif(os.name == "nt", reason="sessions are a POSIX concept")
+def test_spawn_detached_starts_child_in_its_own_session(tmp_path: Path):
+    marker = tmp_path / "sid.txt"
+    script = (
+        "import os, sys\n"
+        "tmp = sys.argv[1] + '.tmp'\n"
+        "open(tmp, 'w').write(str(os.getsid(0)))\n"
+        "os.replace(tmp, sys.argv[1])\n"
+    )
+
+    baseline = rust_bridge.debug_open_handles()
+    ok, pid, error = rust_bridge.spawn_detached(
+        [sys.executable, "-c", script, str(marker)], str(tmp_path)
+    )
+    assert (ok, error) == (True, "")
+    assert pid > 0
+
+    deadline = time.monotonic() + 10.0
+    while not marker.exists() and time.monotonic() < deadline:
+        time.sleep(0.05)
+    assert int(marker.read_text(encoding="utf-8")) != os.getsid(0)
+    while rust_bridge.debug_open_handles() != baseline and time.monotonic() < deadline:
+        time.sleep(0.05)
+    assert rust_bridge.debug_open_handles() == baseline
+
+    ok, _, error = rust_bridge.spawn_detached([str(tmp_path / "no-such-binary")], str(tmp_path))
+    assert ok is False and error
+
+
+@pytest.mark.unit
+def test_hunks_status_flags_only_conflicting_hunks(tmp_path: Path):
+    target = tmp_path / "f.txt"
+    target.write_text("a\nb\nc\nd\nlocal edit\nf\n", encoding="utf-8")
+    patch = "@@ -1,2 +1,2 @@\n a\n-b\n+B\n@@ -5,2 +5,2 @@\n-e\n+E\n f\n"
+
+    assert rust_bridge.hunks_status(str(target), patch) == (True, [(0, True), (1, False)], "")
+
+
+@pytest.mark.unit
+def test_read_text_bom_strips_and_reports_bom(tmp_path: Path):
+    with_bom = tmp_path / "bom.txt"
+    with_bom.write_bytes(b"\xef\xbb\xbfhello")
+    plain = tmp_path / "plain.txt"
+    plain.write_bytes(b"hello")
+
+    assert rust_bridge.read_text_bom(str(with_bom)) == (True, "hello", True, "")
+    assert rust_bridge.read_text_bom(str(plain)) == (True, "hello", False, "")
+
+
+@pytest.mark.unit
+def test_check_argv_paths_reports_missing_paths_but_not_flags(tmp_path: Path):
+    (tmp_path / "src").mkdir()
+    (tmp_path / "src" / "main.py").write_text("", encoding="utf-8")
+    argv = ["ruff", "--config=cfg/x.toml", "src/main.py", "src/mian.py", "https://a/b", "--fix"]
+
+    ok, missing, _ = rust_bridge.check_argv_paths(argv, str(tmp_path))
+
+    assert (ok, missing) == (False, ["src/mian.py"])
+    assert rust_bridge.check_argv_paths(["ls", "src/main.py"], str(tmp_path)) == (True, [], "")
+
+
+@pytest.mark.unit
+def test_hash_with_previous_resumes_over_appended_content(tmp_path: Path):
+    log = tmp_path / "agent.log"
+    log.write_bytes(b"x" * 100)
+
+    ok, first, state, error = rust_bridge.hash_with_previous(str(log), b"", 0)
+    assert (ok, error) == (True, "")
+    assert first == hashlib.sha256(b"x" * 100).hexdigest()
+
+    with log.open("ab") as handle:
+        handle.write(b"appended line\n")
+    ok, resumed, _, _ = rust_bridge.hash_with_previous(str(log), state, 100)
+
+    assert ok is True
+    assert resumed == hashlib.sha256(log.read_bytes()).hexdigest()
+    assert rust_bridge.hash_with_previous(str(log), state, 7)[0] is False
+
+
+@pytest.mark.unit
+@pytest.mark.skipif(os.name == "nt", reason="Windows processes need SYSTEMROOT")
+def test_execute_hermetic_does_not_leak_host_environment(tmp_path: Path, monkeypatch):
+    monkeypatch.setenv("CLAWLET_HOST_SECRET", "leak")
+    script = "import os, json; print(json.dumps(sorted(os.environ)))"
+
+    success, _, stdout, _, error = rust_bridge.execute_hermetic(
+        [sys.executable, "-c", script],
+        str(tmp_path),
+        10.0,
+        [os.path.dirname(sys.executable)],
+        [("APP_MODE", "test")],
+    )
+
+    assert (success, error) == (True, "")
+    names = json.loads(stdout)
+    assert "CLAWLET_HOST_SECRET" not in names
+    assert {"APP_MODE", "PATH"} <= set(names)
+
+
+@pytest.mark.unit
+def test_git_blob_hash_matches_git_object_id(tmp_path: Path):
+    target = tmp_path / "hello.txt"
+    target.write_bytes(b"hello world\n")
+
+    # `git hash-object hello.txt` for this content.
+    expected = "3b18e512dba79e4c8300dd08aeb37f8e728b8dad"
+    assert rust_bridge.git_blob_hash(str(target)) == (True, expected, "")
+
+
+@pytest.mark.unit
+def test_tokenize_words_reports_utf8_byte_offsets():
+    content = "déf run_2(x): 42"
+
+    tokens = rust_bridge.tokenize_words(content)
+
+    assert tokens == [("déf", 0, 4), ("run_2", 5, 10), ("x", 11, 12)]
+    encoded = content.encode("utf-8")
+    assert all(encoded[start:end].decode("utf-8") == word for word, start, end in tokens)
+
+
+@pytest.mark.unit
+def test_secure_delete_removes_file_and_rejects_directories(tmp_path: Path):
+    secret = tmp_path / "token.txt"
+    secret.write_text("sk-test-123", encoding="utf-8")
+
+    assert rust_bridge.secure_delete(str(secret)) == (True, "")
+    assert not secret.exists()
+    assert rust_bridge.secure_delete(str(tmp_path))[0] is False
+
+
+@pytest.mark.unit
+def test_detect_moves_reports_relocated_block():
+    helper = "def helper():\n    return 1\n\n"
+    old = "import os\n" + helper + "def main():\n    pass\n"
+    new = "import os\ndef main():\n    pass\n" + helper
+
+    ok, blocks, error = rust_bridge.detect_moves(old, new, 2)
+
+    assert (ok, error) == (True, "")
+    assert len(blocks) == 1
+    old_start, new_start, length = blocks[0]
+    assert old.splitlines()[old_start - 1 : old_start - 1 + length] == new.splitlines()[
+        new_start - 1 : new_start - 1 + length
+    ]
+    assert length >= 2
+
+
+@pytest.mark.unit
+def test_read_text_retry_does_not_retry_missing_files(tmp_path: Path):
+    target = tmp_path / "present.txt"
+    target.write_text("ok", encoding="utf-8")
+    assert rust_bridge.read_text_retry(str(target), 3, 10) == (True, "ok", "")
+
+    started = time.monotonic()
+    ok, _, error = rust_bridge.read_text_retry(str(tmp_path / "missing.txt"), 5, 1000)
+
+    assert ok is False and "retries" not in error
+    assert time.monotonic() - started < 1.0
+
+
+@pytest.mark.unit
+def test_list_dir_detailed_returns_sorted_metadata(tmp_path: Path):
+    (tmp_path / "b.txt").write_text("12345", encoding="utf-8")
+    (tmp_path / "a_dir").mkdir()
+
+    ok, entries, error = rust_bridge.list_dir_detailed(str(tmp_path))
+
+    assert (ok, error) == (True, "")
+    assert [(name, is_dir, is_link) for name, is_dir, _, _, is_link in entries] == [
+        ("a_dir", True, False),
+        ("b.txt", False, False),
+    ]
+    assert entries[1][2] == 5 and entries[1][3] > 0
+
+
+@pytest.mark.unit
+def test_apply_string_edit_requires_a_unique_match(tmp_path: Path):
+    target = tmp_path / "app.py"
+    target.write_text("x = 1\ny = 1\nx = 1\n", encoding="utf-8")
+
+    assert rust_bridge.apply_string_edit(str(target), "y = 1", "y = 2") == (True, "")
+    assert target.read_text(encoding="utf-8") == "x = 1\ny = 2\nx = 1\n"
+
+    ok, error = rust_bridge.apply_string_edit(str(target), "x = 1", "x = 3")
+    assert ok is False and "found 2 occurrences" in error
+    ok, error = rust_bridge.apply_string_edit(str(target), "z = 0", "z = 1")
+    assert ok is False and "not found" in error
+    assert target.read_text(encoding="utf-8") == "x = 1\ny = 2\nx = 1\n"
+
+
+@pytest.mark.unit
+def test_count_occurrences_in_file_counts_across_chunk_boundaries(tmp_path: Path):
+    target = tmp_path / "data.txt"
+    target.write_text("abcabcab" + "cab" * 3 + "aaaa", encoding="utf-8")
+
+    assert rust_bridge.count_occurrences_in_file(str(target), "abc") == (True, 5, "")
+    assert rust_bridge.count_occurrences_in_file(str(target), "aa") == (True, 2, "")
+    assert rust_bridge._count_occurrences_in_file_python(str(target), "abc", chunk_size=2) == (
+        True,
+        5,
+        "",
+    )
+
+
+@pytest.mark.unit
+def test_apply_string_edits_is_all_or_nothing(tmp_path: Path):
+    target = tmp_path / "mod.py"
+    target.write_text("a = 1\nb = 2\n", encoding="utf-8")
+
+    ok, applied, error = rust_bridge.apply_string_edits(
+        str(target), [("a = 1", "a = 10"), ("a = 10", "a = 11"), ("missing", "x")]
+    )
+    assert (ok, applied) == (False, 2)
+    assert error.startswith("edit 2:")
+    assert target.read_text(encoding="utf-8") == "a = 1\nb = 2\n"
+
+    ok, applied, error = rust_bridge.apply_string_edits(
+        str(target), [("a = 1", "a = 10"), ("b = 2", "b = 20")]
+    )
+    assert (ok, applied, error) == (True, 2, "")
+    assert target.read_text(encoding="utf-8") == "a = 10\nb = 20\n"
+
+
+@pytest.mark.unit
+def test_string_edits_to_patch_previews_without_writing(tmp_path: Path):
+    target = tmp_path / "mod.py"
+    target.write_text("a = 1\nb = 2\nc = 3", encoding="utf-8")
+
+    ok, patch, error = rust_bridge.string_edits_to_patch(
+        str(target), [("b = 2", "b = 20"), ("c = 3", "c = 30")], 1
+    )
+    assert ok is True, error
+    assert "-b = 2\n" in patch and "+b = 20\n" in patch
+    assert patch.endswith("+c = 30\n\\ No newline at end of file\n")
+    assert rust_bridge.validate_patch(patch)[0] is True
+    assert target.read_text(encoding="utf-8") == "a = 1\nb = 2\nc = 3"
+
+    ok, patch, error = rust_bridge.string_edits_to_patch(str(target), [("zzz", "y")], 3)
+    assert (ok, patch) == (False, "") and "not found" in error
+
+
+@pytest.mark.unit
+def test_latest_mtime_ignores_gitignored_files(tmp_path: Path):
+    (tmp_path / ".gitignore").write_text("build/\n", encoding="utf-8")
+    (tmp_path / "src").mkdir()
+    (tmp_path / "src" / "a.py").write_text("a", encoding="utf-8")
+    (tmp_path / "build").mkdir()
+    (tmp_path / "build" / "out.bin").write_text("x", encoding="utf-8")
+    os.utime(tmp_path / ".gitignore", (1000, 1000))
+    os.utime(tmp_path / "src" / "a.py", (2000, 2000))
+    os.utime(tmp_path / "build" / "out.bin", (9000, 9000))
+
+    assert rust_bridge.latest_mtime(str(tmp_path)) == (True, 2000.0, "")
+    assert rust_bridge.latest_mtime(str(tmp_path / "nope"))[0] is False
+
+
+@pytest.mark.unit
+def test_execute_command_to_queue_pushes_chunks_then_sentinel(tmp_path: Path):
+    out: queue.Queue = queue.Queue()
+    script = "import sys; print('hello'); print('oops', file=sys.stderr); sys.exit(3)"
+
+    ok, code, error = rust_bridge.execute_command_to_queue(
+        [sys.executable, "-c", script], str(tmp_path), 10, out
+    )
+
+    items = []
+    while True:
+        item = out.get_nowait()
+        if item is None:
+            break
+        items.append(item)
+    assert (ok, code, error) == (False, 3, "Exit code: 3")
+    assert "".join(text for stream, text in items if stream == "stdout").strip() == "hello"
+    assert "".join(text for stream, text in items if stream == "stderr").strip() == "oops"
+    assert out.empty()
+
+
+@pytest.mark.unit
+def test_hash_cache_reuses_digest_until_mtime_or_size_changes(tmp_path: Path):
+    target = tmp_path / "data.txt"
+    target.write_bytes(b"one")
+    os.utime(target, (1000, 1000))
+    cache = rust_bridge.HashCache()
+
+    ok, first, error = cache.get(str(target))
+    assert ok is True, error
+    assert first == hashlib.sha256(b"one").hexdigest()
+
+    target.write_bytes(b"two")
+    os.utime(target, (1000, 1000))
+    assert cache.get(str(target)) == (True, first, "")
+
+    os.utime(target, (2000, 2000))
+    assert cache.get(str(target)) == (True, hashlib.sha256(b"two").hexdigest(), "")
+    assert cache.get(str(tmp_path / "missing"))[0] is False
+
+
+@pytest.mark.unit
+def test_hash_cache_skips_caching_when_file_changes_while_hashing(tmp_path: Path, monkeypatch):
+    target = tmp_path / "data.txt"
+    target.write_bytes(b"one")
+    os.utime(target, (1000, 1000))
+    real_sha256_file = rust_bridge._sha256_file
+
+    def racing_sha256_file(path: str) -> str:
+        target.write_bytes(b"two")
+        os.utime(target, (2000, 2000))
+        return real_sha256_file(path)
+
+    cache = rust_bridge.HashCache()
+    cache._rust = None
+    monkeypatch.setattr(rust_bridge, "_sha256_file", racing_sha256_file)
+    assert cache.get(str(target)) == (True, hashlib.sha256(b"two").hexdigest(), "")
+    assert str(target) not in cache._entries
+
+    monkeypatch.setattr(rust_bridge, "_sha256_file", real_sha256_file)
+    assert cache.get(str(target)) == (True, hashlib.sha256(b"two").hexdigest(), "")
+    assert str(target) in cache._entries
+
+
+@pytest.mark.unit
+def test_validate_patch_against_file_checks_range_and_context(tmp_path: Path):
+    target = tmp_path / "a.txt"
+    target.write_text("one\ntwo\nthree\n", encoding="utf-8")
+    good = "--- a/a.txt\n+++ b/a.txt\n@@ -2,2 +2,2 @@\n two\n-three\n+THREE\n"
+    short = "--- a/a.txt\n+++ b/a.txt\n@@ -3,2 +3,2 @@\n three\n-four\n+FOUR\n"
+    wrong = "--- a/a.txt\n+++ b/a.txt\n@@ -1,2 +1,2 @@\n one\n-too\n+TWO\n"
+
+    assert rust_bridge.validate_patch_against_file(str(target), good) == (True, "ok")
+    assert rust_bridge.validate_patch_against_file(str(target), short) == (
+        False,
+        "Hunk 1 needs lines 3-4 but file has 3 lines",
+    )
+    assert rust_bridge.validate_patch_against_file(str(target), wrong) == (
+        False,
+        "Hunk 1 context mismatch at line 2",
+    )
+
+
+@pytest.mark.unit
+def test_truncate_to_last_bytes_keeps_whole_trailing_lines(tmp_path: Path):
+    log = tmp_path / "agent.log"
+    log.write_bytes(b"first line\nsecond\nthird\n")
+
+    assert rust_bridge.truncate_to_last_bytes(str(log), 100) == (True, 24, "")
+    assert rust_bridge.truncate_to_last_bytes(str(log), 10) == (True, 6, "")
+    assert log.read_bytes() == b"third\n"
+
+    log.write_bytes(b"aaaa\nbbbb\n")
+    assert rust_bridge.truncate_to_last_bytes(str(log), 5) == (True, 5, "")
+    assert log.read_bytes() == b"bbbb\n"
+
+
+@pytest.mark.unit
+def test_read_expanded_uses_display_columns(tmp_path: Path):
+    target = tmp_path / "table.txt"
+    target.write_text("a\tb\n\tc\n漢\tx\né\ty\n", encoding="utf-8")
+
+    ok, text, error = rust_bridge.read_expanded(str(target), 4)
+
+    assert ok is True, error
+    assert text == "a   b\n    c\n漢  x\né   y\n"
+
+
+@pytest.mark.unit
+def test_diff_ops_reconstructs_new_text_when_applied_bottom_up():
+    old = "a\nb\nc\nd\n"
+    new = "a\nB\nc\nd\ne\n"
+
+    ops = rust_bridge.diff_ops(old, new)
+    assert ops == [("replace", 2, 1, "B\n"), ("insert", 5, 0, "e\n")]
+
+    lines = old.splitlines(keepends=True)
+    for _, at_line, count, text in reversed(ops):
+        lines[at_line - 1 : at_line - 1 + count] = text.splitlines(keepends=True)
+    assert "".join(lines) == new
+    assert rust_bridge.diff_ops("x\ny\n", "y\n") == [("delete", 1, 1, "")]
+
+
+@pytest.mark.unit
+def test_read_snapshot_returns_content_with_matching_size(tmp_path: Path):
+    log = tmp_path / "active.log"
+    log.write_text("line one\nline two\n", encoding="utf-8")
+
+    assert rust_bridge.read_snapshot(str(log)) == (True, "line one\nline two\n", 18, "")
+    assert rust_bridge.read_snapshot(str(tmp_path / "missing.log"))[0] is False
+
+
+@pytest.mark.unit
+def test_offset_to_position_counts_characters_not_bytes():
+    content = "ab\nçé x\n"
+
+    assert rust_bridge.offset_to_position(content, 0) == (True, 1, 1, "")
+    assert rust_bridge.offset_to_position(content, 3) == (True, 2, 1, "")
+    assert rust_bridge.offset_to_position(content, 7) == (True, 2, 3, "")
+    assert rust_bridge.offset_to_position(content, 10) == (True, 3, 1, "")
+    assert rust_bridge.offset_to_position(content, 4)[0] is False
+    assert rust_bridge.offset_to_position(content, 11)[0] is False
+
+
+@pytest.mark.unit
+def test_position_to_offset_inverts_offset_to_position_and_clamps_columns():
+    content = "ab\nçé x\n"
+
+    for offset in (0, 3, 5, 7, 10):
+        ok, line, column, _ = rust_bridge.offset_to_position(content, offset)
+        assert ok is True
+        assert rust_bridge.position_to_offset(content, line, column) == (True, offset, "")
+    assert rust_bridge.position_to_offset(content, 1, 99) == (True, 2, "")
+    assert rust_bridge.position_to_offset(content, 4, 1)[0] is False
+    assert rust_bridge.position_to_offset("ab\r\ncd\r\n", 1, 99) == (True, 2, "")
+    assert rust_bridge.position_to_offset("ab\r\ncd\r\n", 2, 99) == (True, 6, "")
+
+
+@pytest.mark.unit
+def test_ends_with_newline_reads_final_byte(tmp_path: Path):
+    target = tmp_path / "a.txt"
+    target.write_bytes(b"x\n")
+    assert rust_bridge.ends_with_newline(str(target)) == (True, True, "")
+    target.write_bytes(b"x")
+    assert rust_bridge.ends_with_newline(str(target)) == (True, False, "")
+    target.write_bytes(b"")
+    assert rust_bridge.ends_with_newline(str(target)) == (True, False, "")
+    assert rust_bridge.ends_with_newline(str(tmp_path / "missing"))[0] is False
+
+
+@pytest.mark.unit
+def test_execute_command_status_classifies_outcomes(tmp_path: Path):
+    def run(script: str, timeout: float = 10):
+        return rust_bridge.execute_command_status(
+            [sys.executable, "-c", script], str(tmp_path), timeout
+        )
+
+    assert run("print('hi')") == ("ok", 0, 0, "hi\n", "")
+    assert run("import sys; sys.exit(4)")[:3] == ("failed", 4, 0)
+    assert run("import time; time.sleep(5)", timeout=0.5)[:3] == ("timeout", -1, 0)
+    if os.name == "posix":
+        assert run("import os, signal; os.kill(os.getpid(), signal.SIGTERM)")[:3] == (
+            "signal",
+            -1,
+            15,
+        )
+    kind, code, _, _, stderr = rust_bridge.execute_command_status(
+        [str(tmp_path / "no-such-binary")], str(tmp_path), 10
+    )
+    assert (kind, code) == ("spawn_error", -1) and stderr
+
+
+@pytest.mark.unit
+@pytest.mark.skipif(os.name == "nt", reason="symlinks need privileges on Windows")
+def test_find_symlinks_reports_links_without_following(tmp_path: Path):
+    (tmp_path / "real").mkdir()
+    (tmp_path / "real" / "f.txt").write_text("x", encoding="utf-8")
+    os.symlink("real", tmp_path / "dirlink")
+    os.symlink("/etc/passwd", tmp_path / "real" / "escape")
+    os.symlink("gone", tmp_path / "dangling")
+
+    assert rust_bridge.find_symlinks(str(tmp_path)) == (
+        True,
+        [("dangling", "gone"), ("dirlink", "real"), ("real/escape", "/etc/passwd")],
+        "",
+    )
+
+
+@pytest.mark.unit
+def test_preview_patch_on_file_regenerates_diff_without_writing(tmp_path: Path):
+    target = tmp_path / "a.txt"
+    original = "".join(f"line {i}\n" for i in range(1, 11))
+    target.write_text(original, encoding="utf-8")
+    patch = "--- x\n+++ y\n@@ -4,3 +4,3 @@\n line 4\n-line 5\n+LINE 5\n line 6\n"
+
+    ok, preview, error = rust_bridge.preview_patch_on_file(str(target), patch, 1)
+
+    assert ok is True, error
+    assert preview.startswith(f"--- a/{target}\n+++ b/{target}\n@@ -4,3 +4,3 @@\n")
+    assert " line 4\n-line 5\n+LINE 5\n line 6\n" in preview
+    assert target.read_text(encoding="utf-8") == original
+
+    stale = patch.replace(" line 4\n", " line four\n")
+    ok, preview, error = rust_bridge.preview_patch_on_file(str(target), stale, 3)
+    assert (ok, preview) == (False, "") and "does not apply" in error
+
+
+@pytest.mark.unit
+def test_merge3_preview_merges_disjoint_edits_and_counts_conflicts():
+    base = "a\nb\nc\nd\ne\n"
+    ours = "A\nb\nc\nd\ne\n"
+    theirs = "a\nb\nc\nd\nE\n"
+    assert rust_bridge.merge3_preview(base, ours, theirs) == (True, "A\nb\nc\nd\nE\n", 0, "")
+    assert rust_bridge.merge3_preview(base, ours, ours) == (True, ours, 0, "")
+
+    ok, merged, conflicts, error = rust_bridge.merge3_preview(
+        base, "a\nb\nX\nd\ne\n", "a\nb\nY\nd\ne\n"
+    )
+    assert (ok, conflicts, error) == (True, 1, "")
+    assert merged == (
+        "a\nb\n<<<<<<< ours\nX\n||||||| base\nc\n=======\nY\n>>>>>>> theirs\nd\ne\n"
+    )
+
+
+@pytest.mark.unit
+def test_execute_parallel_runs_concurrently_and_keeps_input_order(tmp_path: Path):
+    commands = [
+        [sys.executable, "-c", f"import time; time.sleep(0.5); print({i})"] for i in range(4)
+    ]
+    commands.append([sys.executable, "-c", "import sys; sys.exit(2)"])
+
+    started = time.monotonic()
+    results = rust_bridge.execute_parallel(commands, str(tmp_path), 10, 5)
+    elapsed = time.monotonic() - started
+
+    assert [result[2].strip() for result in results[:4]] == ["0", "1", "2", "3"]
+    assert all(result[:2] == (True, 0) for result in results[:4])
+    assert results[4][:2] == (False, 2)
+    assert elapsed < 1.8
+
+
+@pytest.mark.unit
+def test_read_with_language_uses_extension_then_shebang(tmp_path: Path):
+    (tmp_path / "lib.rs").write_text("fn main() {}\n", encoding="utf-8")
+    (tmp_path / "tool").write_text("#!/usr/bin/env python3\nprint(1)\n", encoding="utf-8")
+    (tmp_path / "run").write_text("#!/bin/bash\necho hi\n", encoding="utf-8")
+    (tmp_path / "notes").write_text("hello\n", encoding="utf-8")
+
+    assert rust_bridge.read_with_language(str(tmp_path / "lib.rs")) == (
+        True,
+        "fn main() {}\n",
+        "rust",
+        "",
+    )
+    assert rust_bridge.read_with_language(str(tmp_path / "tool"))[2] == "python"
+    assert rust_bridge.read_with_language(str(tmp_path / "run"))[2] == "shell"
+    assert rust_bridge.read_with_language(str(tmp_path / "notes"))[2] == "text"
+
+
+@pytest.mark.unit
+def test_verify_manifest_reports_missing_mismatched_and_extra(tmp_path: Path):
+    (tmp_path / ".gitignore").write_text("build/\n", encoding="utf-8")
+    (tmp_path / "keep.txt").write_text("keep", encoding="utf-8")
+    (tmp_path / "edit.txt").write_text("before", encoding="utf-8")
+    (tmp_path / "gone.txt").write_text("gone", encoding="utf-8")
+    (tmp_path / "build").mkdir()
+    (tmp_path / "build" / "out.o").write_text("obj", encoding="utf-8")
+    ok, manifest, error = rust_bridge.build_manifest(str(tmp_path))
+    assert ok is True, error
+    manifest.append(("build/stale.o", 3, "0" * 64))
+
+    (tmp_path / "edit.txt").write_text("after!", encoding="utf-8")
+    (tmp_path / "gone.txt").unlink()
+    (tmp_path / "new.txt").write_text("new", encoding="utf-8")
+    (tmp_path / "build" / "extra.o").write_text("obj", encoding="utf-8")
+
+    assert rust_bridge.verify_manifest(str(tmp_path), manifest) == (
+        True,
+        ["gone.txt"],
+        ["edit.txt"],
+        ["new.txt"],
+        "",
+    )
+    ok, missing, _, extra, _ = rust_bridge.verify_manifest(str(tmp_path), manifest, False)
+    assert missing == ["build/stale.o", "gone.txt"]
+    assert extra == ["build/extra.o", "build/out.o", "new.txt"]
+
+
+@pytest.mark.unit
+def test_read_without_comments_drops_comment_lines(tmp_path: Path):
+    target = tmp_path / "settings.ini"
+    target.write_text("# header\nkey = 1  # note\n\n    # indented\nother = 2\n", encoding="utf-8")
+
+    assert rust_bridge.read_without_comments(str(target), "#") == (
+        True,
+        "key = 1  # note\n\nother = 2\n",
+        "",
+    )
+    assert rust_bridge.read_without_comments(str(target), "#", keep_blank_lines=False)[1] == (
+        "key = 1  # note\nother = 2\n"
+    )
+    assert rust_bridge.read_without_comments(str(target), "")[0] is False
+
+
+@pytest.mark.unit
+def test_patch_coverage_counts_touched_lines_against_new_size(tmp_path: Path):
+    target = tmp_path / "a.txt"
+    target.write_text("".join(f"{i}\n" for i in range(10)), encoding="utf-8")
+    small = "--- a\n+++ b\n@@ -2,1 +2,2 @@\n 1\n+new\n"
+    rewrite = "--- a\n+++ b\n@@ -1,2 +1,1 @@\n-0\n-1\n+x\n"
+
+    assert rust_bridge.patch_coverage(str(target), small) == (True, 1 / 11, "")
+    ok, fraction, _ = rust_bridge.patch_coverage(str(target), rewrite)
+    assert ok is True and fraction == pytest.approx(3 / 9)
+    assert rust_bridge.patch_coverage(str(target), "")[0] is False
+
+
+@pytest.mark.unit
+def test_execute_until_pattern_kills_on_matching_line(tmp_path: Path):
+    script = (
+        "import sys, time\n"
+        "print('starting', flush=True)\n"
+        "print('ERROR: token=abc', file=sys.stderr, flush=True)\n"
+        "time.sleep(10)\n"
+    )
+    started = time.monotonic()
+    ok, code, stdout, stderr, error, aborted, line = rust_bridge.execute_until_pattern(
+        [sys.executable, "-c", script], str(tmp_path), 20, r"token=\w+"
+    )
+
+    assert time.monotonic() - started < 5
+    assert (ok, code, aborted, line) == (False, -1, True, "ERROR: token=abc")
+    assert "starting" in stdout and error
+
+    result = rust_bridge.execute_until_pattern(
+        [sys.executable, "-c", "print('fine')"], str(tmp_path), 10, "token="
+    )
+    assert result == (True, 0, "fine\n", "", "", False, "")
+
+    tail_only = "import sys; sys.stdout.write('ok\\nSECRET'); sys.exit(3)"
+    result = rust_bridge.execute_until_pattern(
+        [sys.executable, "-c", tail_only], str(tmp_path), 10, "SECRET"
+    )
+    assert result == (False, 3, "ok\nSECRET", "", "Exit code: 3", False, "SECRET")
+
+
+@pytest.mark.unit
+def test_ranges_to_hunks_keeps_only_selected_regions():
+    old = "".join(f"line {i}\n" for i in range(1, 31))
+    new = old.replace("line 3\n", "LINE 3\n").replace("line 20\n", "LINE 20\nextra\n")
+
+    ok, patch, error = rust_bridge.ranges_to_hunks(old, new, [(18, 22)], 1)
+
+    assert ok is True, error
+    assert patch == (
+        "--- a\n+++ b\n@@ -19,3 +19,4 @@\n line 19\n-line 20\n+LINE 20\n+extra\n line 21\n"
+    )
+    assert rust_bridge.ranges_to_hunks(old, new, [(10, 12)], 1) == (True, "", "")
+
+    ok, both, _ = rust_bridge.ranges_to_hunks(old, new, [(1, 30)], 1)
+    assert both.count("@@ -") == 2
+
+
+@pytest.mark.unit
+def test_find_duplicate_lines_reports_repeated_blocks(tmp_path: Path, monkeypatch):
+    block = "x = load()\ny = x + 1\nsave(y)\nlog(y)\n"
+    target = tmp_path / "dup.py"
+    content = "start\n" + block + "middle\n\n\n\n" + block + "\n\n\nend\n"
+    target.write_text(content, encoding="utf-8")
+
+    assert rust_bridge.find_duplicate_lines(str(target), 3) == (True, [(2, 10, 4)], "")
+    assert rust_bridge.find_duplicate_lines(str(target), 5) == (True, [], "")
+    assert rust_bridge.find_duplicate_lines(str(target), 0)[0] is False
+
+    monkeypatch.setattr(bridge_fallbacks, "_DUPLICATE_LINES_MAX_LINES", 10)
+    ok, runs, error = bridge_fallbacks._find_duplicate_lines_python(str(target), 3)
+    assert (ok, runs) == (False, [])
+    assert "more than 10 lines" in error
+
+
+@pytest.mark.unit
+def test_patch_max_line_reads_hunk_headers():
+    patch = (
+        "--- a\n+++ b\n"
+        "@@ -2,2 +2,3 @@\n x\n-y\n+Y\n+Z\n"
+        "@@ -40,3 +41,2 @@\n p\n-q\n r\n"
+    )
+
+    assert rust_bridge.patch_max_line(patch) == (True, 42, 42, "")
+    assert rust_bridge.patch_max_line("--- a\n+++ b\n@@ -7,0 +8,1 @@\n+new\n") == (True, 7, 8, "")
+    assert rust_bridge.patch_max_line("")[0] is False
+
+
+@pytest.mark.unit
+def test_list_modified_since_filters_by_mtime(tmp_path: Path):
+    (tmp_path / ".gitignore").write_text("*.log\n", encoding="utf-8")
+    for name, mtime in (("old.py", 1000), ("new.py", 3000), ("sub/new.md", 4000), ("x.log", 5000)):
+        path = tmp_path / name
+        path.parent.mkdir(exist_ok=True)
+        path.write_text("x", encoding="utf-8")
+        os.utime(path, (mtime, mtime))
+    os.utime(tmp_path / ".gitignore", (1000, 1000))
+
+    assert rust_bridge.list_modified_since(str(tmp_path), 2000) == (
+        True,
+        ["new.py", "sub/new.md"],
+        "",
+    )
+
+
+@pytest.mark.unit
+def test_diff_trees_renames_pairs_exact_and_similar_moves(tmp_path: Path):
+    a, b = tmp_path / "a", tmp_path / "b"
+    body = "".join(f"line {i}\n" for i in range(20))
+    for root in (a, b):
+        root.mkdir()
+        (root / "same.txt").write_text("same", encoding="utf-8")
+    (a / "old_name.py").write_text("print('moved')\n", encoding="utf-8")
+    (b / "new_name.py").write_text("print('moved')\n", encoding="utf-8")
+    (a / "big.txt").write_text(body, encoding="utf-8")
+    (b / "big_renamed.txt").write_text(body.replace("line 7\n", "line seven\n"), encoding="utf-8")
+    (a / "gone.txt").write_text("unrelated\n", encoding="utf-8")
+    (b / "fresh.txt").write_text("brand new\n", encoding="utf-8")
+
+    assert rust_bridge.diff_trees_renames(str(a), str(b)) == (
+        True,
+        [("old_name.py", "new_name.py")],
+        ["big_renamed.txt", "fresh.txt"],
+        ["big.txt", "gone.txt"],
+        [],
+        "",
+    )
+    ok, renames, added, removed, _, _ = rust_bridge.diff_trees_renames(str(a), str(b), 0.8)
+    assert renames == [("big.txt", "big_renamed.txt"), ("old_name.py", "new_name.py")]
+    assert (added, removed) == (["fresh.txt"], ["gone.txt"])
+
+
+@pytest.mark.unit
+def test_write_text_file_atomic_durable_replaces_content(tmp_path: Path):
+    target = tmp_path / "state.json"
+    target.write_text("{}", encoding="utf-8")
+
+    assert rust_bridge.write_text_file_atomic(str(target), '{"v": "é"}', durable=True) == (
+        True,
+        11,
+        "",
+    )
+    assert target.read_text(encoding="utf-8") == '{"v": "é"}'
+    assert sorted(p.name for p in tmp_path.iterdir()) == ["state.json"]
+    assert rust_bridge.write_text_file_atomic(str(tmp_path / "no" / "f"), "x")[0] is False
+
+
+@pytest.mark.unit
+def test_read_bounded_stops_at_first_limit(tmp_path: Path):
+    target = tmp_path / "a.txt"
+    target.write_text("one\ntwo\nthree\n", encoding="utf-8")
+    wide = tmp_path / "wide.txt"
+    wide.write_text("é" * 10 + "\n", encoding="utf-8")
+
+    assert rust_bridge.read_bounded(str(target), 2, 1000) == (True, "one\ntwo\n", True, "")
+    assert rust_bridge.read_bounded(str(target), 10, 6) == (True, "one\ntw", True, "")
+    assert rust_bridge.read_bounded(str(target), 3, 14) == (True, "one\ntwo\nthree\n", False, "")
+    assert rust_bridge.read_bounded(str(wide), 10, 5) == (True, "éé", True, "")
+
+
+@pytest.mark.unit
+def test_execute_command_hash_only_digests_each_stream(tmp_path: Path):
+    script = "import sys; sys.stdout.write('out'); sys.stderr.write('err')"
+
+    ok, code, digest, error = rust_bridge.execute_command_hash_only(
+        [sys.executable, "-c", script], str(tmp_path), 10
+    )
+
+    expected = hashlib.sha256(
+        hashlib.sha256(b"out").digest() + hashlib.sha256(b"err").digest()
+    ).hexdigest()
+    assert (ok, code, digest, error) == (True, 0, expected, "")
+
+
+@pytest.mark.unit
+def test_read_lines_capped_truncates_oversized_lines(tmp_path: Path):
+    target = tmp_path / "bundle.min.js"
+    target.write_text("short\n" + "x" * 200_000 + "\nafter\n", encoding="utf-8")
+
+    assert rust_bridge.read_lines_capped(str(target), 8) == (
+        True,
+        ["short", "xxxxxxxx…", "after"],
+        True,
+        "",
+    )
+    assert rust_bridge.read_lines_capped(str(target), 300_000)[2] is False
+
+    target.write_bytes(b"12345678\r\nnext\r\n")
+    assert rust_bridge.read_lines_capped(str(target), 8) == (True, ["12345678", "next"], False, "")
+
+
+@pytest.mark.unit
+def test_increment_counter_file_is_safe_across_threads(tmp_path: Path):
+    from concurrent.futures import ThreadPoolExecutor
+
+    counter = tmp_path / "run_id"
+
+    assert rust_bridge.increment_counter_file(str(counter)) == (True, 1, "")
+    with ThreadPoolExecutor(max_workers=8) as pool:
+        results = list(
+            pool.map(lambda _: rust_bridge.increment_counter_file(str(counter)), range(40))
+        )
+
+    assert sorted(value for _, value, _ in results) == list(range(2, 42))
+    assert counter.read_text(encoding="ascii").strip() == "41"
+    counter.write_text("nope", encoding="ascii")
+    assert rust_bridge.increment_counter_file(str(counter))[0] is False
+
+
+@pytest.mark.unit
+def test_validate_patch_can_reject_blank_only_context():
+    blank = "--- a\n+++ b\n@@ -1,3 +1,3 @@\n \n-x\n+y\n \n"
+    real = "--- a\n+++ b\n@@ -1,3 +1,3 @@\n \n-x\n+y\n def f():\n"
+
+    assert rust_bridge.validate_patch(blank)[0] is True
+    assert rust_bridge.validate_patch(blank, reject_blank_context=True) == (
+        False,
+        "Hunk 1 has only blank context lines",
+    )
+    assert rust_bridge.validate_patch(real, reject_blank_context=True) == (True, "ok")
+
+
+@pytest.mark.unit
+def test_hash_and_size_come_from_one_read(tmp_path: Path):
+    data = os.urandom(200_000)
+    target = tmp_path / "blob.bin"
+    target.write_bytes(data)
+
+    assert rust_bridge.hash_and_size(str(target)) == (
+        True,
+        hashlib.sha256(data).hexdigest(),
+        200_000,
+        "",
+    )
+    assert rust_bridge.hash_and_size(str(tmp_path / "missing"))[0] is False
+
+
+@pytest.mark.unit
+def test_execute_command_timestamped_records_arrival_times(tmp_path: Path):
+    script = "import time; print('a', flush=True); time.sleep(0.4); print('b', flush=True)"
+
+    ok, code, entries, error = rust_bridge.execute_command_timestamped(
+        [sys.executable, "-c", script], str(tmp_path), 10
+    )
+
+    assert (ok, code, error) == (True, 0, "")
+    assert "".join(text for _, _, text in entries).split() == ["a", "b"]
+    first_b = next(at for at, _, text in entries if "b" in text)
+    assert first_b - entries[0][0] >= 0.3
+    assert [at for at, _, _ in entries] == sorted(at for at, _, _ in entries)
+
+
+@pytest.mark.unit
+def test_collapse_repeats_folds_identical_runs():
+    text = "start\nwarn: x\nwarn: x\nwarn: x\nend\nend"
+
+    assert rust_bridge.collapse_repeats(text) == (
+        "start\nwarn: x (repeated 3 times)\nend (repeated 2 times)"
+    )
+    assert rust_bridge.collapse_repeats("a\nb\na\n") == "a\nb\na\n"
+    assert rust_bridge.collapse_repeats("") == ""
+
+
+@pytest.mark.unit
+def test_execute_command_argv_collapses_repeats_without_rust_core(tmp_path: Path, monkeypatch):
+    monkeypatch.setitem(sys.modules, "clawlet_rust_core", None)
+    argv = [sys.executable, "-c", "print('spam\\n' * 3, end='')"]
+
+    assert rust_bridge.execute_command_argv(argv, str(tmp_path), 10.0) is None
+    result = rust_bridge.execute_command_argv(argv, str(tmp_path), 10.0, True)
+    assert result == (True, 0, "spam (repeated 3 times)\n", "", "")
+
+
+@pytest.mark.unit
+def test_read_dual_returns_bytes_and_lossy_text(tmp_path: Path):
+    good = tmp_path / "good.txt"
+    good.write_bytes("héllo".encode("utf-8"))
+    bad = tmp_path / "bad.bin"
+    bad.write_bytes(b"ok\xff")
+
+    assert rust_bridge.read_dual(str(good)) == (True, "héllo".encode("utf-8"), "héllo", True, "")
+    assert rust_bridge.read_dual(str(bad)) == (True, b"ok\xff", "ok�", False, "")
+    assert rust_bridge.read_dual(str(tmp_path / "missing"))[0] is False
+
+
+@pytest.mark.unit
+def test_apply_patch_ignore_ws_tolerates_trailing_whitespace_drift():
+    original = "def f():   \n    return 1\t\n\nx = f()\n"
+    patch = "--- a\n+++ b\n@@ -1,2 +1,2 @@\n def f():\n-    return 1\n+    return 2\n"
+
+    assert rust_bridge.apply_patch_ignore_ws(original, patch) == (
+        True,
+        "def f():   \n    return 2\n\nx = f()\n",
+        "",
+    )
+    ok, _, error = rust_bridge.apply_patch_ignore_ws(original.replace("f()", "g()"), patch)
+    assert ok is False and "does not apply" in error
+
+
+@pytest.mark.unit
+def test_hash_tree_progress_reports_and_matches_hash_tree(tmp_path: Path):
+    for i in range(25):
+        (tmp_path / f"f{i}.txt").write_text(str(i), encoding="utf-8")
+    calls = []
+
+    ok, digest, error = rust_bridge.hash_tree_progress(
+        str(tmp_path), lambda done, total: calls.append((done, total))
+    )
+
+    assert ok is True, error
+    assert digest == rust_bridge.hash_tree(str(tmp_path))[1]
+    assert calls[-1] == (25, 25)
+    assert len(calls) < 25
+
+    def boom(done, total):
+        raise RuntimeError("stop")
+
+    assert rust_bridge.hash_tree_progress(str(tmp_path), boom) == (
+        False,
+        "",
+        "Progress callback failed: stop",
+    )
+
+
+@pytest.mark.unit
+def test_compile_regex_reports_groups_or_error():
+    assert rust_bridge.compile_regex(r"(\w+)@(?P<host>[\w.]+)(?:\.com)?") == (True, 2, "")
+    assert rust_bridge.compile_regex("plain") == (True, 0, "")
+    ok, groups, error = rust_bridge.compile_regex("(unclosed")
+    assert (ok, groups) == (False, 0) and error.startswith("Invalid regex:")
+
+
+@pytest.mark.unit
+def test_byte_histogram_samples_from_start(tmp_path: Path):
+    target = tmp_path / "blob.bin"
+    target.write_bytes(b"\x00\x00ab" + b"z" * 100)
+
+    ok, counts, error = rust_bridge.byte_histogram(str(target), 5)
+    assert (ok, error, len(counts)) == (True, "", 256)
+    assert (counts[0], counts[ord("a")], counts[ord("b")], counts[ord("z")]) == (2, 1, 1, 1)
+    assert sum(rust_bridge.byte_histogram(str(target), 10_000)[1]) == 104
+
+
+@pytest.mark.unit
+def test_diff_to_patch_file_writes_applicable_diff(tmp_path: Path):
+    old = tmp_path / "old.txt"
+    new = tmp_path / "new.txt"
+    out = tmp_path / "change.patch"
+    old.write_text("a\nb\nc\n", encoding="utf-8")
+    new.write_text("a\nB\nc\n", encoding="utf-8")
+
+    ok, length, error = rust_bridge.diff_to_patch_file(str(old), str(new), str(out), 1)
+
+    assert ok is True, error
+    patch = out.read_text(encoding="utf-8")
+    assert length == len(patch.encode("utf-8"))
+    assert rust_bridge.apply_patch_ignore_ws(old.read_text(encoding="utf-8"), patch) == (
+        True,
+        "a\nB\nc\n",
+        "",
+    )
+    assert rust_bridge.diff_to_patch_file(str(old), str(old), str(out), 3) == (True, 0, "")
+
+
+@pytest.mark.unit
+def test_hash_with_dispatches_by_algorithm():
+    assert rust_bridge.hash_with(b"abc", "sha256") == (True, hashlib.sha256(b"abc").hexdigest(), "")
+    assert rust_bridge.hash_with("abc", "sha512")[1] == hashlib.sha512(b"abc").hexdigest()
+    assert rust_bridge.hash_with(b"abc", "md5")[1] == "900150983cd24fb0d6963f7d28e17f72"
+    assert rust_bridge.hash_with(b"abc", "sha256")[1] == rust_bridge.fast_hash("abc")
+    assert rust_bridge.hash_with(b"abc", "crc32") == (False, "", "Unknown hash algorithm: crc32")
+
+
+@pytest.mark.unit
+def test_grep_context_numbered_merges_windows(tmp_path: Path):
+    target = tmp_path / "log.txt"
+    lines = [f"{'ERR' if i in (3, 5, 12) else 'ok'} {i}\n" for i in range(1, 16)]
+    target.write_text("".join(lines), encoding="utf-8")
+
+    ok, text, error = rust_bridge.grep_context_numbered(str(target), r"^ERR", 1, 1)
+
+    assert ok is True, error
+    assert text == (
+        "2-ok 2\n3:ERR 3\n4-ok 4\n5:ERR 5\n6-ok 6\n--\n11-ok 11\n12:ERR 12\n13-ok 13\n"
+    )
+    assert rust_bridge.grep_context_numbered(str(target), "nomatch", 2, 2) == (True, "", "")
+
+
+@pytest.mark.unit
+def test_patch_within_paths_flags_out_of_scope_files():
+    def file_patch(old: str, new: str) -> str:
+        return f"--- {old}\n+++ {new}\n@@ -1,1 +1,1 @@\n-x\n+y\n"
+
+    inside = file_patch("a/src/app.py", "b/src/app.py")
+    outside = inside + file_patch("a/src/../.env", "b/src/../.env")
+    created = file_patch("/dev/null", "b/docs/new.md")
+
+    assert rust_bridge.patch_within_paths(inside, ["src/"]) == (True, [], "")
+    assert rust_bridge.patch_within_paths(outside, ["src"]) == (False, [".env"], "")
+    assert rust_bridge.patch_within_paths(created, ["src", "docs"]) == (True, [], "")
+    assert rust_bridge.patch_within_paths("@@ -1 +1 @@\n-x\n+y\n", ["src"])[0] is False
+
+
+@pytest.mark.unit
+def test_stream_transform_rewrites_and_drops_lines(tmp_path: Path):
+    src = tmp_path / "in.txt"
+    dst = tmp_path / "out.txt"
+    src.write_bytes(b"keep one\r\ndrop me\nkeep two")
+
+    ok, written, error = rust_bridge.stream_transform(
+        str(src), str(dst), lambda line: None if line.startswith("drop") else line.upper()
+    )
+    assert (ok, written, error) == (True, 18, "")
+    assert dst.read_bytes() == b"KEEP ONE\r\nKEEP TWO"
+
+    def boom(line):
+        raise ValueError("bad line")
+
+    assert rust_bridge.stream_transform(str(src), str(dst), boom) == (
+        False,
+        0,
+        "Transform callback failed: bad line",
+    )
+    assert dst.read_bytes() == b"KEEP ONE\r\nKEEP TWO"
+    assert sorted(p.name for p in tmp_path.iterdir()) == ["in.txt", "out.txt"]
+
+
+@pytest.mark.unit
+def test_timed_read_and_write_report_durations(tmp_path: Path):
+    target = tmp_path / "t.txt"
+
+    ok, written, seconds, error = rust_bridge.timed_write(str(target), "héllo")
+    assert (ok, written, error) == (True, 6, "") and seconds >= 0.0
+
+    ok, content, seconds, error = rust_bridge.timed_read(str(target))
+    assert (ok, content, error) == (True, "héllo", "") and seconds >= 0.0
+    assert rust_bridge.timed_read(str(tmp_path / "missing"))[0] is False
+
+
+@pytest.mark.unit
+def test_split_file_at_line_preserves_endings(tmp_path: Path):
+    src = tmp_path / "all.txt"
+    first, second = tmp_path / "first.txt", tmp_path / "second.txt"
+    src.write_bytes(b"one\r\ntwo\nthree")
+
+    assert rust_bridge.split_file_at_line(str(src), 1, str(first), str(second)) == (True, "")
+    assert (first.read_bytes(), second.read_bytes()) == (b"one\r\n", b"two\nthree")
+
+    rust_bridge.split_file_at_line(str(src), 0, str(first), str(second))
+    assert (first.read_bytes(), second.read_bytes()) == (b"", b"one\r\ntwo\nthree")
+    rust_bridge.split_file_at_line(str(src), 99, str(first), str(second))
+    assert (first.read_bytes(), second.read_bytes()) == (b"one\r\ntwo\nthree", b"")
+
+
+@pytest.mark.unit
+def test_concat_files_joins_with_separator(tmp_path: Path):
+    parts = []
+    for name, text in (("a.txt", "alpha\n"), ("b.txt", "beta\n")):
+        (tmp_path / name).write_text(text, encoding="utf-8")
+        parts.append(str(tmp_path / name))
+    out = tmp_path / "bundle.txt"
+    missing = str(tmp_path / "missing.txt")
+
+    assert rust_bridge.concat_files(parts, str(out), "---\n") == (True, 15, "")
+    assert out.read_text(encoding="utf-8") == "alpha\n---\nbeta\n"
+    assert rust_bridge.concat_files([missing, *parts], str(out), "|", skip_missing=True)[1] == 12
+    ok, _, error = rust_bridge.concat_files([*parts, missing], str(out), "|")
+    assert ok is False and "missing.txt" in error
+    assert out.read_text(encoding="utf-8") == "alpha\n|beta\n"
+
+
+@pytest.mark.unit
+def test_detect_output_encoding_recognises_utf8_and_utf16(tmp_path: Path):
+    def detect(payload: bytes):
+        script = f"import sys; sys.stdout.buffer.write({payload!r})"
+        return rust_bridge.detect_output_encoding([sys.executable, "-c", script], str(tmp_path), 10)
+
+    assert detect("héllo wörld".encode("utf-8")) == (True, "UTF-8", "")
+    assert detect("hello world".encode("utf-16-le")) == (True, "UTF-16LE", "")
+    ok, name, _ = detect("café".encode("cp1252"))
+    assert ok is True and name != "UTF-8"
+
+
+@pytest.mark.unit
+def test_read_byte_range_drops_partial_characters(tmp_path: Path):
+    target = tmp_path / "t.txt"
+    target.write_bytes("aé€b".encode("utf-8"))
+
+    assert rust_bridge.read_byte_range(str(target), 0, 3) == (True, "aé", "")
+    assert rust_bridge.read_byte_range(str(target), 2, 5) == (True, "", "")
+    assert rust_bridge.read_byte_range(str(target), 2, 100) == (True, "€b", "")
+    assert rust_bridge.read_byte_range(str(target), 1, 5) == (True, "é", "")
+    assert rust_bridge.read_byte_range(str(target), 4, 2)[0] is False
+
+
+@pytest.mark.unit
+def test_execute_with_retries_records_each_attempt(tmp_path: Path):
+    marker = tmp_path / "count"
+    script = (
+        "import pathlib, sys\n"
+        f"p = pathlib.Path({str(marker)!r})\n"
+        "n = int(p.read_text()) + 1 if p.exists() else 1\n"
+        "p.write_text(str(n))\n"
+        "sys.exit(0 if n >= 3 else 7)\n"
+    )
+
+    ok, code, _, _, error, attempts = rust_bridge.execute_with_retries(
+        [sys.executable, "-c", script], str(tmp_path), 10, 5, 0.01, jitter_fraction=0.5, seed=1
+    )
+
+    assert (ok, code, error) == (True, 0, "")
+    assert [attempt_code for _, attempt_code in attempts] == [7, 7, 0]
+    assert all(duration > 0 for duration, _ in attempts)
+
+    ok, code, _, _, _, attempts = rust_bridge.execute_with_retries(
+        [sys.executable, "-c", "import sys; sys.exit(2)"], str(tmp_path), 10, 2, 0.01
+    )
+    assert (ok, code, len(attempts)) == (False, 2, 2)
+
+
+@pytest.mark.unit
+def test_patch_roundtrips_checks_reverse_restores_original():
+    original = "a\nb\nc\n"
+    patch = "--- a/f\n+++ b/f\n@@ -1,3 +1,3 @@\n a\n-b\n+B\n c\n"
+
+    assert rust_bridge.patch_roundtrips(original, patch) == (True, True, "")
+    ok, roundtrips, error = rust_bridge.patch_roundtrips("x\ny\nz\n", patch)
+    assert (ok, roundtrips) == (False, False) and error.startswith("Forward apply failed")
+
+
+@pytest.mark.unit
+def test_list_by_mtime_returns_newest_first(tmp_path: Path):
+    (tmp_path / ".gitignore").write_text("*.tmp\n", encoding="utf-8")
+    os.utime(tmp_path / ".gitignore", (100, 100))
+    for name, mtime in (("a.py", 1000), ("b.py", 3000), ("c.py", 2000), ("d.tmp", 9000)):
+        (tmp_path / name).write_text("x", encoding="utf-8")
+        os.utime(tmp_path / name, (mtime, mtime))
+
+    assert rust_bridge.list_by_mtime(str(tmp_path), 2) == (
+        True,
+        [("b.py", 3000.0), ("c.py", 2000.0)],
+        "",
+    )
+    assert rust_bridge.list_by_mtime(str(tmp_path), 1, respect_gitignore=False)[1] == [
+        ("d.tmp", 9000.0)
+    ]
+
+
+@pytest.mark.unit
+def test_write_text_file_converts_line_endings_and_roundtrips_crlf(tmp_path: Path, monkeypatch):
+    class FakeCore:
+        @staticmethod
+        def write_text_file(path, content):
+            data = content.encode("utf-8")
+            Path(path).write_bytes(data)
+            return True, len(data), ""
+
+    monkeypatch.setitem(sys.modules, "clawlet_rust_core", FakeCore)
+    target = tmp_path / "win.txt"
+    target.write_bytes(b"one\r\ntwo\r\n")
+
+    ok, text, _, _ = rust_bridge.read_text_bom(str(target))
+    assert ok is True
+    assert rust_bridge.write_text_file(str(target), text + "three\n", "crlf") == (True, 17, "")
+    assert target.read_bytes() == b"one\r\ntwo\r\nthree\r\n"
+    rust_bridge.write_text_file(str(target), "a\r\nb\rc\n", "lf")
+    assert target.read_bytes() == b"a\nb\nc\n"
+    rust_bridge.write_text_file(str(target), "a\r\nb\n", "keep")
+    assert target.read_bytes() == b"a\r\nb\n"
+    assert rust_bridge.write_text_file(str(target), "x", "unix") == (
+        False,
+        0,
+        "Unknown line_ending: unix",
+    )
+
+
+@pytest.mark.unit
+def test_write_text_file_converts_line_endings_without_rust_core(tmp_path: Path, monkeypatch):
+    monkeypatch.setitem(sys.modules, "clawlet_rust_core", None)
+    target = tmp_path / "win.txt"
+    target.write_bytes(b"one\r\ntwo\r\n")
+
+    ok, text, _, _ = rust_bridge.read_text_bom(str(target))
+    assert ok is True
+    assert rust_bridge.write_text_file(str(target), text + "three\n", "crlf") == (True, 17, "")
+    assert target.read_bytes() == b"one\r\ntwo\r\nthree\r\n"
+    assert rust_bridge.write_text_file(str(target), "a\r\nb\rc\n", "lf") == (True, 6, "")
+    assert target.read_bytes() == b"a\nb\nc\n"
+    assert rust_bridge.write_text_file(str(target), "a\nb\n", "keep") is None
+    assert rust_bridge.write_text_file(str(target), "x", "unix") == (
+        False,
+        0,
+        "Unknown line_ending: unix",
+    )
+
+
+@pytest.mark.unit
+def test_repo_relative_path_finds_enclosing_repo(tmp_path: Path):
+    repo = tmp_path / "repo"
+    (repo / ".git").mkdir(parents=True)
+    (repo / "src" / "pkg").mkdir(parents=True)
+    source = repo / "src" / "pkg" / "mod.py"
+    source.write_text("x", encoding="utf-8")
+    root = os.path.realpath(repo)
+
+    assert rust_bridge.repo_relative_path(str(source)) == (True, root, "src/pkg/mod.py", "")
+    assert rust_bridge.repo_relative_path(str(repo)) == (True, root, ".", "")
+    outside = tmp_path / "loose.txt"
+    outside.write_text("x", encoding="utf-8")
+    ok, _, _, error = rust_bridge.repo_relative_path(str(outside))
+    if not any((Path(p) / ".git").exists() for p in Path(os.path.realpath(tmp_path)).parents):
+        assert ok is False and "Not inside a git repository" in error
+
+
+@pytest.mark.unit
+def test_execute_json_flags_machine_readable_stdout(tmp_path: Path):
+    def run(script: str):
+        return rust_bridge.execute_json([sys.executable, "-c", script], str(tmp_path), 10)
+
+    success, code, stdout, is_json, error = run("print('{\"ok\": [1, 2]}')")
+    assert (success, code, is_json, error) == (True, 0, True, "")
+    assert json.loads(stdout) == {"ok": [1, 2]}
+    assert run("print('not json')")[3] is False
+    assert run("pass")[3] is False
+    assert run("print('NaN')")[3] is False
+    assert run("print('[1, -Infinity]')")[3] is False
+
+
+@pytest.mark.unit
+def test_rename_aware_fingerprint_ignores_git_metadata():
+    git_rename = (
+        "diff --git a/old.py b/new.py\n"
+        "similarity index 90%\n"
+        "rename from old.py\n"
+        "rename to new.py\n"
+        "index 1111111..2222222 100644\n"
+        "--- a/old.py\n"
+        "+++ b/new.py\n"
+        "@@ -1,3 +1,3 @@\n"
+        " a\n"
+        "-b\n"
+        "+B\n"
+        " c\n"
+    )
+    minimized = "--- old.py\n+++ new.py\n@@ -2 +2 @@\n-b\n+B\n"
+    ok, digest, error = rust_bridge.rename_aware_fingerprint(git_rename)
+    assert (ok, error) == (True, "") and len(digest) == 64
+    assert rust_bridge.rename_aware_fingerprint(minimized)[1] == digest
+
+    pure = "diff --git a/x b/y\nsimilarity index 100%\nrename from x\nrename to y\n"
+    other = "diff --git a/x b/z\nsimilarity index 100%\nrename from x\nrename to z\n"
+    fingerprint = rust_bridge.rename_aware_fingerprint
+    assert fingerprint(pure)[1] != fingerprint(other)[1]
+    assert fingerprint("--- a/x\n+++ b/y\n")[1] == fingerprint(pure)[1]
+    assert rust_bridge.rename_aware_fingerprint("not a patch\n")[0] is False
+
+    mode_lines = "old mode 100644\nnew mode 100755\n"
+    with_mode = git_rename.replace("index ", mode_lines + "index ")
+    ok, minimized_mode, _ = rust_bridge.minimize_patch(with_mode)
+    assert ok is True
+    assert fingerprint(minimized_mode)[1] == fingerprint(with_mode)[1] == digest
+
+
+@pytest.mark.unit
+def test_glob_match_many_applies_includes_and_excludes(tmp_path: Path):
+    for rel in ["a.py", "src/b.py", "src/c.txt", "src/gen/d.py", "build/e.py"]:
+        (tmp_path / rel).parent.mkdir(parents=True, exist_ok=True)
+        (tmp_path / rel).write_text("x\n", encoding="utf-8")
+    (tmp_path / ".gitignore").write_text("build/\n", encoding="utf-8")
+
+    ok, paths, error = rust_bridge.glob_match_many(
+        str(tmp_path), ["**/*.py", "*.txt", "src/*.txt"], ["src/gen/**"]
+    )
+    assert (ok, error) == (True, "")
+    assert paths == ["a.py", "build/e.py", "src/b.py", "src/c.txt"]
+
+    ignored = rust_bridge.glob_match_many(str(tmp_path), ["**/*.py"], [], respect_gitignore=True)
+    assert ignored == (True, ["a.py", "src/b.py", "src/gen/d.py"], "")
+    assert rust_bridge.glob_match_many(str(tmp_path), [], [])[0] is False